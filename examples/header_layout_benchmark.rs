@@ -0,0 +1,154 @@
+//! Chunk Header Layout Benchmark
+//!
+//! Compares the default 64-byte chunk header against the split-counter
+//! header (`ArenaLayout::split_counters`) under mixed load:
+//!
+//! - **Producers** append small payloads (CAS on `used`, bump `entry_count`)
+//! - **Consumers** resolve and acknowledge them (bump `ack_count`)
+//!
+//! With the default header both sides hammer the same cache line; with the
+//! split header they do not. Payloads are small and handles travel in
+//! batches so header traffic dominates. Needs at least
+//! `PRODUCERS + CONSUMERS` cores to show a difference.
+//!
+//! Run: `cargo run --example header_layout_benchmark --release`
+
+use crossbeam_channel::bounded;
+use stable_fragmented_buffer::{ArenaLayout, Config, PinnedBlobStore, SharedConfig};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// ── Configuration ────────────────────────────────────────────────────────
+
+/// Chunk size: large enough that every message lands in the first chunk
+const CHUNK_SIZE: usize = 64 * 1024 * 1024; // 64 MB
+/// Payload size per message
+const PAYLOAD_SIZE: usize = 32;
+/// Messages per producer thread
+const MESSAGES_PER_PRODUCER: usize = 200_000;
+/// Producer / consumer thread counts
+const PRODUCERS: usize = 2;
+const CONSUMERS: usize = 2;
+/// Handles per channel message
+const BATCH: usize = 256;
+/// Runs per layout (best run is reported)
+const RUNS: usize = 5;
+
+fn main() {
+    println!("╔══════════════════════════════════════════════════════════════╗");
+    println!("║           Chunk Header Layout Benchmark                     ║");
+    println!("╚══════════════════════════════════════════════════════════════╝");
+    println!();
+    println!("  Producers:      {}", PRODUCERS);
+    println!("  Consumers:      {}", CONSUMERS);
+    println!("  Payload size:   {} B", PAYLOAD_SIZE);
+    println!("  Messages:       {}", PRODUCERS * MESSAGES_PER_PRODUCER);
+    println!("  Runs:           {} (best reported)", RUNS);
+    println!();
+
+    let compact = best_of(false);
+    let split = best_of(true);
+
+    let ops = (PRODUCERS * MESSAGES_PER_PRODUCER) as f64;
+    let compact_rate = ops / compact.as_secs_f64() / 1e6;
+    let split_rate = ops / split.as_secs_f64() / 1e6;
+
+    println!("━━━ Results ━━━");
+    println!(
+        "  Default header (64 B):  {:>8.2?}  {:>6.2} M msg/s",
+        compact, compact_rate
+    );
+    println!(
+        "  Split header (192 B):   {:>8.2?}  {:>6.2} M msg/s",
+        split, split_rate
+    );
+    println!(
+        "  Speedup:                {:>6.2}x",
+        split_rate / compact_rate
+    );
+    println!();
+}
+
+fn best_of(split_counters: bool) -> Duration {
+    let label = if split_counters { "split" } else { "default" };
+    (0..RUNS)
+        .map(|run| {
+            let elapsed = run_once(split_counters, run);
+            println!("  [{}] run {}: {:.2?}", label, run + 1, elapsed);
+            elapsed
+        })
+        .min()
+        .unwrap()
+}
+
+/// One producer/consumer round over a fresh namespace.
+fn run_once(split_counters: bool, run: usize) -> Duration {
+    let namespace = format!("hlb{}{}", split_counters as u8, run);
+    let shared_config = SharedConfig {
+        layout: ArenaLayout {
+            split_counters,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let store = Arc::new(
+        PinnedBlobStore::new_shared_with_config(
+            Config::default(),
+            &namespace,
+            CHUNK_SIZE,
+            shared_config,
+        )
+        .expect("Failed to create shared store"),
+    );
+
+    let (tx, rx) = bounded::<Vec<_>>(64);
+    let start = Instant::now();
+
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|_| {
+            let store = Arc::clone(&store);
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let payload = [0xABu8; PAYLOAD_SIZE];
+                let mut batch = Vec::with_capacity(BATCH);
+                for _ in 0..MESSAGES_PER_PRODUCER {
+                    batch.push(store.append_shared(&payload).expect("append_shared failed"));
+                    if batch.len() == BATCH {
+                        tx.send(std::mem::replace(&mut batch, Vec::with_capacity(BATCH)))
+                            .unwrap();
+                    }
+                }
+                if !batch.is_empty() {
+                    tx.send(batch).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(tx);
+
+    let consumers: Vec<_> = (0..CONSUMERS)
+        .map(|_| {
+            let store = Arc::clone(&store);
+            let rx = rx.clone();
+            thread::spawn(move || {
+                for batch in rx {
+                    for handle in &batch {
+                        assert!(store.resolve(handle).is_some());
+                        assert!(store.acknowledge_shared(handle));
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for p in producers {
+        p.join().unwrap();
+    }
+    for c in consumers {
+        c.join().unwrap();
+    }
+
+    // Drop unlinks the namespace
+    start.elapsed()
+}
@@ -79,7 +79,7 @@ fn main() {
             reused_ids.push(handle.page_id());
             print!("{} ", handle.page_id());
         }
-        println!("");
+        println!();
 
         if reused_ids.iter().any(|&id| id < 5) {
             println!("♻️  PERFECT! System automatically reused freed pages.");
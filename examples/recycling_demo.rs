@@ -26,7 +26,7 @@ fn main() {
     let mut handles = Vec::new();
     let data = vec![0u8; 1024 * 512]; // 512KB
 
-    for i in 0..10 {
+    for _ in 0..10 {
         // 10 chunks = 5 pages
         let handle = store.append(&data).unwrap();
         handles.push(handle);
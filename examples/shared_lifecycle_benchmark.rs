@@ -17,7 +17,7 @@ use std::time::{Duration, Instant};
 // ── Configuration ────────────────────────────────────────────────────────
 
 /// Chunk size: 1 MB (small for faster testing — use 32 MB in production)
-const CHUNK_SIZE: usize = 1 * 1024 * 1024; // 1 MB
+const CHUNK_SIZE: usize = 1024 * 1024; // 1 MB
 /// Payload size per message
 const PAYLOAD_SIZE: usize = 4 * 1024; // 4 KB
 /// Total data to push (must exceed CHUNK_SIZE to trigger multi-chunk)
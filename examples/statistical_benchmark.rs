@@ -5,6 +5,10 @@
 use stable_fragmented_buffer::{Config, PinnedBlobStore};
 use std::time::Instant;
 
+/// Generates (data_vec, total_bytes) for one workload
+type Workload = Box<dyn Fn() -> (Vec<Vec<u8>>, usize)>;
+
+#[allow(dead_code)] // get_* percentiles are collected but not printed yet
 struct BenchmarkResult {
     config_name: &'static str,
     workload: &'static str,
@@ -68,7 +72,7 @@ fn benchmark_workload(
 
     for _ in 0..iterations {
         let store = PinnedBlobStore::new(config.clone()).unwrap();
-        let (data_vec, total_bytes) = data_generator();
+        let (data_vec, _total_bytes) = data_generator();
 
         // Measure append
         let start = Instant::now();
@@ -124,7 +128,7 @@ fn main() {
     ];
 
     // Define workloads
-    let workloads: Vec<(&str, Box<dyn Fn() -> (Vec<Vec<u8>>, usize)>)> = vec![
+    let workloads: Vec<(&str, Workload)> = vec![
         (
             "Small (1KB)",
             Box::new(|| {
@@ -184,7 +188,7 @@ fn main() {
                 config_name,
                 workload_name,
                 iterations,
-                || generator(),
+                generator,
             );
             result.print();
         }
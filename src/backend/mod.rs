@@ -46,6 +46,12 @@ pub(crate) trait StorageBackend: Send + Sync {
     /// Time: O(log n) for BTreeMap removal.
     fn remove_page(&mut self, id: u32) -> bool;
 
+    /// Move the page at `from` to the free id `to`. Its data stays where
+    /// it is, so pointers into it remain valid.
+    ///
+    /// Time: O(log n) for BTreeMap removal and insertion.
+    fn rename_page(&mut self, from: u32, to: u32) -> bool;
+
     /// Get list of all currently active page IDs.
     ///
     /// Time: O(n) — iterates all pages.
@@ -45,6 +45,18 @@ impl StorageBackend for SegmentedBackend {
         self.pages.remove(&id).is_some()
     }
 
+    fn rename_page(&mut self, from: u32, to: u32) -> bool {
+        if self.pages.contains_key(&to) {
+            return false;
+        }
+        let Some(mut page) = self.pages.remove(&from) else {
+            return false;
+        };
+        page.id = to;
+        self.pages.insert(to, page);
+        true
+    }
+
     fn active_page_ids(&self) -> Vec<u32> {
         self.pages.keys().copied().collect()
     }
@@ -6,10 +6,49 @@
 //!
 //! All synchronisation uses atomics embedded in the shared memory itself,
 //! so no OS-level IPC is required for the hot path.
-
-use crate::types::{now_ms, BlobError, OverflowHandle, Result};
+//!
+//! ## Layout versions
+//!
+//! The control file's `version` field selects the on-disk layout:
+//!
+//! - **v1**: 32-bit `chunk_size`, `used` and generation counters (≤ 4 GB chunks).
+//! - **v2** (default for new arenas): 64-bit `chunk_size`, `used` and
+//!   generation counters, plus an `incompat_flags` word for future features.
+//!
+//! `attach` negotiates by reading the version: this library attaches to
+//! v1 and v2 arenas read/write, using the matching chunk-header layout.
+//! Older libraries check `version == 1` and therefore refuse v2 arenas
+//! instead of misreading them; this library likewise refuses unknown
+//! versions and unknown `incompat_flags` bits with `UnsupportedLayout`.
+//!
+//! ## Authenticated handles
+//!
+//! Arenas created with `ArenaLayout::authenticated_handles` keep a random
+//! SipHash key in the control file and tag every handle they issue.
+//! `resolve` and `acknowledge` reject handles whose tag does not verify
+//! before touching any chunk. Every handle, authenticated or not, must
+//! also lie below its chunk's `used` watermark.
+//!
+//! ## Entry records
+//!
+//! Arenas created with `ArenaLayout::entry_records` store a small record in
+//! front of every payload for per-entry state: its own expiry from
+//! `append_with_ttl`, consumer-group acks, reference counts, claims and
+//! deletion. Handles must line up with a record of their size. The records
+//! also let `cleanup_chunks` report entries it drops unacknowledged as dead
+//! letters.
+
+use crate::types::{
+    check_align, copy_gathered, gathered_len, now_ms, pod_bytes, pod_bytes_mut, pod_zeroed,
+    seek_position, ArenaLayout, BlobError, CompactHandle, DeadLetter, DeadLetterHandle, Handle,
+    OverflowHandle, Pod, Result, SharedConfig, DEAD_LETTER_CAPACITY, SLICE_TAG,
+};
+use siphasher::sip::SipHasher24;
 use std::collections::BTreeMap;
 use std::ffi::CString;
+use std::hash::Hasher;
+use std::io::IoSlice;
+use std::ops::RangeBounds;
 use std::ptr::{self, NonNull};
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
@@ -17,11 +56,11 @@ use std::sync::Arc;
 // ── Constants ─────────────────────────────────────────────────────────────
 
 const CTRL_MAGIC: u64 = 0x444D58505F4F5646; // "DMXP_OVF"
-const CTRL_VERSION: u32 = 1;
+const CTRL_VERSION_V1: u32 = 1;
+const CTRL_VERSION_V2: u32 = 2;
 pub(crate) const CHUNK_HEADER_SIZE: usize = 64; // bytes reserved at the start of each chunk
-
-/// Minimum chunk size: must exceed the 64-byte header to have usable data space.
-const MIN_CHUNK_SIZE: usize = CHUNK_HEADER_SIZE + 1;
+/// Header size of the split-counter layout (three cache lines).
+pub(crate) const SPLIT_CHUNK_HEADER_SIZE: usize = 192;
 
 /// Default chunk size: 32 MB.
 pub const DEFAULT_CHUNK_SIZE: usize = 32 * 1024 * 1024;
@@ -31,16 +70,78 @@ pub const DEFAULT_CHUNK_SIZE: usize = 32 * 1024 * 1024;
 // Offset  Field              Size   Description
 // ------  -----------------  -----  ----------------------------------
 //   0     magic              8      0x444D58505F4F5646
-//   8     version            4      Protocol version (1)
-//  12     chunk_size         4      Bytes per data chunk
+//   8     version            4      Layout version (1 or 2)
+//  12     chunk_size         4      v1: bytes per data chunk; v2: 0 (see 72)
 //  16     write_head         4      Current active chunk for appends (AtomicU32)
 //  20     chunk_count        4      Highest allocated chunk id + 1 (AtomicU32)
-//  24     generation         4      Global generation counter (AtomicU32)
-//  28     _reserved         100     Padding to 128 bytes
+//  24     generation         4      v1: global generation counter (AtomicU32); v2: 0
+//  28     flags              4      CTRL_FLAG_* bits (AtomicU32)
+//  32     ttl_ms             8      Shared TTL (AtomicU64)
+//  40     decay_timeout_ms   8      Shared decay timeout (AtomicU64)
+//  48     byte_budget        8      Max live chunk bytes, 0 = unlimited (AtomicU64)
+//  56     max_chunks         4      Max chunk ids, 0 = unlimited (AtomicU32)
+//  60     prefetch_threshold 4      f32 bit pattern (AtomicU32)
+//  64     live_chunks        4      Chunks currently present in /dev/shm (AtomicU32)
+//  68     _reserved          60     Padding to 128 bytes (v1)
 //
-// Total: 128 bytes.
-
-const CTRL_SIZE: usize = 128;
+// Total (v1): 128 bytes. Layout v2 keeps bytes 0..68 and continues:
+//
+//  68     incompat_flags     4      Features a reader must understand (CTRL_INCOMPAT_*)
+//  72     chunk_size         8      Bytes per data chunk (u64)
+//  80     generation         8      Global generation counter (AtomicU64)
+//  88     handle_key         16     SipHash key for handle tags (CTRL_INCOMPAT_AUTH_HANDLES)
+// 104     groups_mask        4      Bit n set once consumer group n is registered (AtomicU32)
+// 108     align              4      Default payload alignment, 0 = 1 (AtomicU32, see below)
+// 112     _reserved          8
+// 120     instance_id        8      Same offset as in v1 (see below)
+// 128     group_table        992    MAX_GROUPS consumer-group slots of 32 bytes
+// 1120    _reserved          2976   Padding to 4096 bytes
+//
+// Total (v2): 4096 bytes.
+//
+// Bytes 28.. were reserved (zero) in arenas created before the shared policy
+// existed, so `CTRL_FLAG_SHARED_CONFIG` tells readers whether 32..68 are valid.
+//
+// Both versions keep a random, nonzero `instance_id` (u64) at byte 120,
+// inside the v1 padding. It is 0 in arenas created before it existed.
+// Likewise `align` (u32) at byte 108, which such arenas read as 0.
+//
+// Consumer-group slot (arenas with entry records only):
+//
+// Offset  Field              Size   Description
+//   0     state              4      GROUP_FREE / GROUP_WRITING / GROUP_REGISTERED (AtomicU32)
+//   4     name_len           4      Name length in bytes
+//   8     name               24     UTF-8 name, zero padded
+
+const CTRL_SIZE_V1: usize = 128;
+const CTRL_SIZE_V2: usize = 4096;
+
+/// Chunk headers use the split (192-byte) layout.
+const CTRL_INCOMPAT_SPLIT_COUNTERS: u32 = 1 << 0;
+/// Handles carry a tag that readers must verify against `handle_key`.
+const CTRL_INCOMPAT_AUTH_HANDLES: u32 = 1 << 1;
+/// Every payload is preceded by an entry record.
+const CTRL_INCOMPAT_ENTRY_RECORDS: u32 = 1 << 2;
+
+/// `incompat_flags` bits this library understands. Attaching to an arena
+/// with any other bit set fails with `UnsupportedLayout`.
+const CTRL_INCOMPAT_KNOWN: u32 =
+    CTRL_INCOMPAT_SPLIT_COUNTERS | CTRL_INCOMPAT_AUTH_HANDLES | CTRL_INCOMPAT_ENTRY_RECORDS;
+
+/// Set once the shared policy fields (ttl .. live_chunks) have been written.
+const CTRL_FLAG_SHARED_CONFIG: u32 = 1 << 0;
+
+/// Maximum number of consumer groups per namespace.
+pub const MAX_GROUPS: usize = 31;
+/// Maximum length of a consumer-group name in bytes.
+pub const MAX_GROUP_NAME_LEN: usize = 24;
+
+const CTRL_GROUPS_MASK: usize = 104;
+const CTRL_GROUP_TABLE: usize = 128;
+const GROUP_SLOT_SIZE: usize = 32;
+const GROUP_FREE: u32 = 0;
+const GROUP_WRITING: u32 = 1;
+const GROUP_REGISTERED: u32 = 2;
 
 /// Raw view over the control file's mmap region.
 struct ControlFile {
@@ -68,8 +169,14 @@ impl ControlFile {
         unsafe { (self.ptr.as_ptr().add(8) as *const u32).read_volatile() }
     }
 
-    fn chunk_size(&self) -> u32 {
-        unsafe { (self.ptr.as_ptr().add(12) as *const u32).read_volatile() }
+    /// Bytes per data chunk, from the version-specific field.
+    fn chunk_size(&self) -> u64 {
+        unsafe {
+            match self.version() {
+                CTRL_VERSION_V1 => (self.ptr.as_ptr().add(12) as *const u32).read_volatile() as u64,
+                _ => (self.ptr.as_ptr().add(72) as *const u64).read_volatile(),
+            }
+        }
     }
 
     fn write_head(&self) -> &AtomicU32 {
@@ -80,31 +187,291 @@ impl ControlFile {
         unsafe { &*(self.ptr.as_ptr().add(20) as *const AtomicU32) }
     }
 
-    fn generation(&self) -> &AtomicU32 {
-        unsafe { &*(self.ptr.as_ptr().add(24) as *const AtomicU32) }
+    /// Bump the global generation counter and return the new value.
+    ///
+    /// v1 arenas keep a 32-bit counter at offset 24, v2 a 64-bit one at 80.
+    fn next_generation(&self) -> u64 {
+        unsafe {
+            match self.version() {
+                CTRL_VERSION_V1 => {
+                    let gen = &*(self.ptr.as_ptr().add(24) as *const AtomicU32);
+                    gen.fetch_add(1, Ordering::AcqRel).wrapping_add(1) as u64
+                }
+                _ => {
+                    let gen = &*(self.ptr.as_ptr().add(80) as *const AtomicU64);
+                    gen.fetch_add(1, Ordering::AcqRel) + 1
+                }
+            }
+        }
+    }
+
+    /// Feature bits a reader must understand (always 0 in v1 arenas).
+    fn incompat_flags(&self) -> u32 {
+        unsafe { (self.ptr.as_ptr().add(68) as *const u32).read_volatile() }
+    }
+
+    /// Chunk-header layout recorded in this control file.
+    fn chunk_layout(&self) -> ChunkLayout {
+        ChunkLayout::for_arena(&self.load_config().layout)
+    }
+
+    /// Random id written at creation; 0 for arenas that predate it.
+    fn instance_id(&self) -> u64 {
+        unsafe { (self.ptr.as_ptr().add(120) as *const u64).read_volatile() }
+    }
+
+    /// SipHash key for handle tags, if the arena authenticates handles.
+    fn handle_key(&self) -> Option<[u64; 2]> {
+        if self.incompat_flags() & CTRL_INCOMPAT_AUTH_HANDLES == 0 {
+            return None;
+        }
+        let p = self.ptr.as_ptr();
+        unsafe {
+            Some([
+                (p.add(88) as *const u64).read_volatile(),
+                (p.add(96) as *const u64).read_volatile(),
+            ])
+        }
+    }
+
+    fn flags(&self) -> &AtomicU32 {
+        unsafe { &*(self.ptr.as_ptr().add(28) as *const AtomicU32) }
+    }
+
+    fn ttl_ms(&self) -> &AtomicU64 {
+        unsafe { &*(self.ptr.as_ptr().add(32) as *const AtomicU64) }
+    }
+
+    fn decay_timeout_ms(&self) -> &AtomicU64 {
+        unsafe { &*(self.ptr.as_ptr().add(40) as *const AtomicU64) }
+    }
+
+    fn byte_budget(&self) -> &AtomicU64 {
+        unsafe { &*(self.ptr.as_ptr().add(48) as *const AtomicU64) }
+    }
+
+    fn max_chunks(&self) -> &AtomicU32 {
+        unsafe { &*(self.ptr.as_ptr().add(56) as *const AtomicU32) }
+    }
+
+    fn prefetch_threshold(&self) -> &AtomicU32 {
+        unsafe { &*(self.ptr.as_ptr().add(60) as *const AtomicU32) }
+    }
+
+    fn live_chunks(&self) -> &AtomicU32 {
+        unsafe { &*(self.ptr.as_ptr().add(64) as *const AtomicU32) }
+    }
+
+    fn align(&self) -> &AtomicU32 {
+        unsafe { &*(self.ptr.as_ptr().add(108) as *const AtomicU32) }
+    }
+
+    // ── Consumer groups ───────────────────────────────────────────────
+
+    fn groups_mask(&self) -> &AtomicU32 {
+        unsafe { &*(self.ptr.as_ptr().add(CTRL_GROUPS_MASK) as *const AtomicU32) }
+    }
+
+    fn group_slot(&self, id: usize) -> *mut u8 {
+        unsafe {
+            self.ptr
+                .as_ptr()
+                .add(CTRL_GROUP_TABLE + id * GROUP_SLOT_SIZE)
+        }
+    }
+
+    fn group_state(&self, id: usize) -> &AtomicU32 {
+        unsafe { &*(self.group_slot(id) as *const AtomicU32) }
+    }
+
+    /// Name of group `id`. Only meaningful once its state reads
+    /// `GROUP_REGISTERED` (Acquire).
+    fn group_name(&self, id: usize) -> &[u8] {
+        let slot = self.group_slot(id);
+        unsafe {
+            let len = (slot.add(4) as *const u32).read_volatile() as usize;
+            std::slice::from_raw_parts(slot.add(8), len.min(MAX_GROUP_NAME_LEN))
+        }
+    }
+
+    /// Id of the group called `name`, claiming the first free slot for it
+    /// if no process has registered it yet.
+    ///
+    /// Slots are claimed in order and a claimer waits out slots that are
+    /// still being written, so two processes registering the same name
+    /// always agree on one id.
+    fn register_group(&self, name: &[u8]) -> Result<u32> {
+        let mut id = 0;
+        while id < MAX_GROUPS {
+            let state = self.group_state(id);
+            match state.load(Ordering::Acquire) {
+                GROUP_REGISTERED if self.group_name(id) == name => return Ok(id as u32),
+                GROUP_WRITING => std::thread::yield_now(),
+                GROUP_FREE => {
+                    if state
+                        .compare_exchange(
+                            GROUP_FREE,
+                            GROUP_WRITING,
+                            Ordering::AcqRel,
+                            Ordering::Acquire,
+                        )
+                        .is_err()
+                    {
+                        continue;
+                    }
+                    let slot = self.group_slot(id);
+                    unsafe {
+                        (slot.add(4) as *mut u32).write_volatile(name.len() as u32);
+                        ptr::copy_nonoverlapping(name.as_ptr(), slot.add(8), name.len());
+                    }
+                    state.store(GROUP_REGISTERED, Ordering::Release);
+                    self.groups_mask().fetch_or(1 << id, Ordering::AcqRel);
+                    return Ok(id as u32);
+                }
+                _ => id += 1,
+            }
+        }
+        Err(BlobError::TooManyGroups { max: MAX_GROUPS })
+    }
+
+    // ── Shared policy ─────────────────────────────────────────────────
+
+    /// Read the shared policy. Each field is loaded independently, so a
+    /// concurrent `store_config` may be observed half-applied.
+    fn load_config(&self) -> SharedConfig {
+        let max_chunks = self.max_chunks().load(Ordering::Acquire);
+        let byte_budget = self.byte_budget().load(Ordering::Acquire);
+        SharedConfig {
+            ttl_ms: self.ttl_ms().load(Ordering::Acquire),
+            decay_timeout_ms: self.decay_timeout_ms().load(Ordering::Acquire),
+            max_chunks: (max_chunks != 0).then_some(max_chunks),
+            prefetch_threshold: f32::from_bits(self.prefetch_threshold().load(Ordering::Acquire)),
+            byte_budget: (byte_budget != 0).then_some(byte_budget),
+            align: self.align().load(Ordering::Acquire).max(1) as usize,
+            layout: ArenaLayout {
+                version: self.version(),
+                split_counters: self.incompat_flags() & CTRL_INCOMPAT_SPLIT_COUNTERS != 0,
+                authenticated_handles: self.incompat_flags() & CTRL_INCOMPAT_AUTH_HANDLES != 0,
+                entry_records: self.incompat_flags() & CTRL_INCOMPAT_ENTRY_RECORDS != 0,
+            },
+        }
+    }
+
+    /// Publish the shared policy and mark it present.
+    fn store_config(&self, config: &SharedConfig) {
+        self.ttl_ms().store(config.ttl_ms, Ordering::Release);
+        self.decay_timeout_ms()
+            .store(config.decay_timeout_ms, Ordering::Release);
+        self.max_chunks()
+            .store(config.max_chunks.unwrap_or(0), Ordering::Release);
+        self.prefetch_threshold()
+            .store(config.prefetch_threshold.to_bits(), Ordering::Release);
+        self.byte_budget()
+            .store(config.byte_budget.unwrap_or(0), Ordering::Release);
+        self.align().store(config.align as u32, Ordering::Release);
+        self.flags()
+            .fetch_or(CTRL_FLAG_SHARED_CONFIG, Ordering::AcqRel);
     }
 
     // ── Initialise (creator only) ─────────────────────────────────────
 
-    unsafe fn init(&self, chunk_size: u32) {
+    /// Initialise the control file for the layout in `config.layout`.
+    ///
+    /// The caller must have mapped `ctrl_size(config.layout.version)` bytes
+    /// and validated that v1 chunk sizes fit in 32 bits. `handle_key` is
+    /// required when `config.layout.authenticated_handles` is set.
+    unsafe fn init(
+        &self,
+        chunk_size: u64,
+        config: &SharedConfig,
+        instance_id: u64,
+        handle_key: Option<[u64; 2]>,
+    ) {
         let p = self.ptr.as_ptr();
+        let version = config.layout.version;
         // Zero everything first
-        ptr::write_bytes(p, 0, CTRL_SIZE);
+        ptr::write_bytes(p, 0, ctrl_size(version));
         // Write header fields
         (p as *mut u64).write(CTRL_MAGIC);
-        (p.add(8) as *mut u32).write(CTRL_VERSION);
-        (p.add(12) as *mut u32).write(chunk_size);
-        // write_head, chunk_count, generation start at 0 (already zeroed)
+        (p.add(8) as *mut u32).write(version);
+        (p.add(120) as *mut u64).write(instance_id);
+        if version == CTRL_VERSION_V1 {
+            (p.add(12) as *mut u32).write(chunk_size as u32);
+        } else {
+            (p.add(72) as *mut u64).write(chunk_size);
+            let mut incompat = 0;
+            if config.layout.split_counters {
+                incompat |= CTRL_INCOMPAT_SPLIT_COUNTERS;
+            }
+            if config.layout.entry_records {
+                incompat |= CTRL_INCOMPAT_ENTRY_RECORDS;
+            }
+            if let Some([k0, k1]) = handle_key {
+                incompat |= CTRL_INCOMPAT_AUTH_HANDLES;
+                (p.add(88) as *mut u64).write(k0);
+                (p.add(96) as *mut u64).write(k1);
+            }
+            (p.add(68) as *mut u32).write(incompat);
+        }
+        // write_head, chunk_count, generation, live_chunks start at 0 (already zeroed)
+        self.store_config(config);
+    }
+
+    /// Adopt the shared policy on attach.
+    ///
+    /// Arenas created before the policy lived in the control file have the
+    /// fields zeroed; the first attacher publishes `fallback` so that every
+    /// later process agrees on the same values. `live_chunks` is seeded
+    /// from `live_chunks(chunk_count)`, which counts the chunk files still
+    /// present.
+    fn adopt_config(&self, fallback: &SharedConfig, live_chunks: impl FnOnce(u32) -> u32) {
+        if self.flags().load(Ordering::Acquire) & CTRL_FLAG_SHARED_CONFIG == 0 {
+            let count = live_chunks(self.chunk_count().load(Ordering::Acquire));
+            let _ =
+                self.live_chunks()
+                    .compare_exchange(0, count, Ordering::AcqRel, Ordering::Acquire);
+            self.store_config(fallback);
+        }
     }
 
+    /// Check the magic and negotiate the layout version.
+    ///
+    /// Only the first `CTRL_SIZE_V1` bytes are read, so this is safe on a
+    /// probe mapping of either version.
     fn validate(&self) -> Result<()> {
         if self.magic() != CTRL_MAGIC {
             return Err(BlobError::InvalidHandle);
         }
-        if self.version() != CTRL_VERSION {
-            return Err(BlobError::InvalidHandle);
+        let version = self.version();
+        let flags = self.incompat_flags();
+        match version {
+            CTRL_VERSION_V1 | CTRL_VERSION_V2 if flags & !CTRL_INCOMPAT_KNOWN == 0 => Ok(()),
+            _ => Err(BlobError::UnsupportedLayout { version, flags }),
         }
-        Ok(())
+    }
+}
+
+/// `N` random words from `/dev/urandom`, for instance ids and handle
+/// keys. Fails with `Io` if the device cannot be read.
+#[cfg(unix)]
+fn random_words<const N: usize>() -> Result<[u64; N]> {
+    use std::io::Read;
+
+    let mut words = [0u64; N];
+    let mut bytes = [0u8; 8];
+    let mut urandom = std::fs::File::open("/dev/urandom")?;
+    for word in &mut words {
+        urandom.read_exact(&mut bytes)?;
+        *word = u64::from_ne_bytes(bytes);
+    }
+    Ok(words)
+}
+
+/// Mapped size of the control file for a given layout version.
+fn ctrl_size(version: u32) -> usize {
+    match version {
+        CTRL_VERSION_V1 => CTRL_SIZE_V1,
+        _ => CTRL_SIZE_V2,
     }
 }
 
@@ -120,8 +487,10 @@ impl Drop for ControlFile {
 
 // ── Chunk Header Layout ──────────────────────────────────────────────────
 //
-// Sits at byte 0 of every data chunk mmap.
+// Sits at byte 0 of every data chunk mmap. The layout follows the arena's
+// control-file version.
 //
+// v1:
 // Offset  Field           Size  Description
 //   0     used            4     Bytes written (AtomicU32, CAS target)
 //   4     generation      4     Recycling generation (AtomicU32)
@@ -129,14 +498,192 @@ impl Drop for ControlFile {
 //  12     ack_count       4     Acknowledged entries (AtomicU32)
 //  16     empty_since     8     Timestamp when all entries became dead (AtomicU64)
 //  24     first_write_ts  8     Timestamp of the first append to this chunk (AtomicU64)
-//  32     _reserved      32     Pad to 64 bytes
+//  32     last_write_ts   8     Timestamp of the newest append to this chunk (AtomicU64)
+//  40     _reserved      24     Pad to 64 bytes
+//
+// v2:
+// Offset  Field           Size  Description
+//   0     used            8     Bytes written (AtomicU64, CAS target)
+//   8     generation      8     Recycling generation (AtomicU64)
+//  16     entry_count     4     Total entries appended (AtomicU32)
+//  20     ack_count       4     Acknowledged entries (AtomicU32)
+//  24     empty_since     8     Timestamp when all entries became dead (AtomicU64)
+//  32     first_write_ts  8     Timestamp of the first append to this chunk (AtomicU64)
+//  40     last_write_ts   8     Timestamp of the newest append to this chunk (AtomicU64)
+//  48     max_expires_at  8     Latest own expiry of any entry (AtomicU64, entry records only)
+//  56     _reserved       8     Pad to 64 bytes
+//
+// v2 with split counters (CTRL_INCOMPAT_SPLIT_COUNTERS), one field group
+// per 64-byte cache line so producers and consumers never share a line:
+// Offset  Field           Size  Description
+//   0     generation      8     Recycling generation — read-mostly line
+//   8     _reserved      56
+//  64     used            8     Producer line: CAS target for append
+//  72     entry_count     4     Producer line
+//  76     _pad            4
+//  80     first_write_ts  8     Producer line
+//  88     last_write_ts   8     Producer line
+//  96     max_expires_at  8     Producer line (entry records only)
+// 104     _reserved      24
+// 128     ack_count       4     Consumer line
+// 132     _pad            4
+// 136     empty_since     8     Consumer line
+// 144     _reserved      48     Pad to 192 bytes
+//
+// Data region starts at offset `header_size` (64, or 192 when split).
+//
+// ── Entry Records ─────────────────────────────────────────────────────────
+//
+// Arenas with CTRL_INCOMPAT_ENTRY_RECORDS put a record in front of every
+// payload. Each entry (record + payload) starts on an 8-byte boundary of the
+// data region, and handle offsets point at the payload, not the record.
+//
+// Offset  Field           Size  Description
+//   0     size            4     Payload length (must match the handle)
+//   4     acks            4     Ack bits received so far (AtomicU32)
+//   8     expires_at      8     Own expiry, ms since epoch (AtomicU64);
+//                               0 = shared TTL from the handle timestamp,
+//                               EXPIRES_DELETED = deleted,
+//                               EXPIRES_PENDING = reserved, not committed
+//  16     required_acks   4     Ack bits that complete the entry: the
+//                               groups registered at append, or
+//                               ACK_UNGROUPED if there were none
+//  20     refs            4     Live references (AtomicU32), 1 at append
+//  24     claim           8     owner << 48 | visibility deadline in ms
+//                               since epoch (AtomicU64); 0 = unclaimed
+//  32     written_at      8     Append (or commit) time, ms since epoch;
+//                               0 in fillers
+//  40     _reserved       8     Keeps payloads 16-aligned when records are
 //
-// Data region starts at offset CHUNK_HEADER_SIZE (64).
+// The first time `acks` cover `required_acks` the entry's initial reference
+// is dropped. It counts towards the chunk's `ack_count` exactly once, when
+// `refs` reaches zero.
+//
+// An aligned append may leave a gap in front of its record. The gap holds a
+// filler entry: a deleted record with no references, counted in both
+// `entry_count` and `ack_count`, whose payload covers the rest of the gap.
+//
+// Claiming an entry on the shared TTL copies that expiry into `expires_at`,
+// so handles rebuilt from the record for redelivery, which lack the original
+// timestamp, still expire on time.
+
+/// Size of the per-entry record in arenas with entry records.
+pub(crate) const ENTRY_RECORD_SIZE: usize = 48;
+const RECORD_SIZE: usize = 0;
+const RECORD_ACKS: usize = 4;
+const RECORD_EXPIRES_AT: usize = 8;
+const RECORD_REQUIRED_ACKS: usize = 16;
+const RECORD_REFS: usize = 20;
+const RECORD_CLAIM: usize = 24;
+const RECORD_WRITTEN_AT: usize = 32;
+
+/// Smallest filler entry: a record and 8 bytes, since a record of size 0
+/// marks an append in flight.
+const FILLER_MIN_SIZE: u64 = ENTRY_RECORD_SIZE as u64 + 8;
+
+/// `expires_at` of a deleted entry: expired long ago, and never renewed.
+const EXPIRES_DELETED: u64 = 1;
+
+/// `expires_at` of a reserved entry that is not committed yet. It reads as
+/// expired until the commit replaces it with 0.
+const EXPIRES_PENDING: u64 = 2;
+
+/// Low bits of a claim word holding the deadline; the owner sits above.
+const CLAIM_DEADLINE_BITS: u32 = 48;
+const CLAIM_DEADLINE_MASK: u64 = (1 << CLAIM_DEADLINE_BITS) - 1;
+
+/// Ack bit required of entries appended while no consumer group was
+/// registered. Above every group bit.
+const ACK_UNGROUPED: u32 = 1 << 31;
+
+/// Field offsets of a chunk header, selected by the arena's layout version.
+#[derive(Debug, Clone, Copy)]
+struct ChunkLayout {
+    /// `used` and `generation` are 64-bit (v2) rather than 32-bit (v1).
+    wide: bool,
+    header_size: usize,
+    used: usize,
+    generation: usize,
+    entry_count: usize,
+    ack_count: usize,
+    empty_since: usize,
+    first_write_ts: usize,
+    last_write_ts: usize,
+    max_expires_at: usize,
+    /// Payloads are preceded by an entry record.
+    entry_records: bool,
+}
+
+const CHUNK_LAYOUT_V1: ChunkLayout = ChunkLayout {
+    wide: false,
+    header_size: CHUNK_HEADER_SIZE,
+    used: 0,
+    generation: 4,
+    entry_count: 8,
+    ack_count: 12,
+    empty_since: 16,
+    first_write_ts: 24,
+    last_write_ts: 32,
+    max_expires_at: 40, // unused: v1 arenas cannot have entry records
+    entry_records: false,
+};
+
+const CHUNK_LAYOUT_V2: ChunkLayout = ChunkLayout {
+    wide: true,
+    header_size: CHUNK_HEADER_SIZE,
+    used: 0,
+    generation: 8,
+    entry_count: 16,
+    ack_count: 20,
+    empty_since: 24,
+    first_write_ts: 32,
+    last_write_ts: 40,
+    max_expires_at: 48,
+    entry_records: false,
+};
+
+const CHUNK_LAYOUT_V2_SPLIT: ChunkLayout = ChunkLayout {
+    wide: true,
+    header_size: SPLIT_CHUNK_HEADER_SIZE,
+    used: 64,
+    generation: 0,
+    entry_count: 72,
+    ack_count: 128,
+    empty_since: 136,
+    first_write_ts: 80,
+    last_write_ts: 88,
+    max_expires_at: 96,
+    entry_records: false,
+};
+
+impl ChunkLayout {
+    fn for_arena(layout: &ArenaLayout) -> Self {
+        let header = match (layout.version, layout.split_counters) {
+            (CTRL_VERSION_V1, _) => CHUNK_LAYOUT_V1,
+            (_, true) => CHUNK_LAYOUT_V2_SPLIT,
+            (_, false) => CHUNK_LAYOUT_V2,
+        };
+        Self {
+            entry_records: layout.entry_records,
+            ..header
+        }
+    }
+
+    /// Bytes of the data region that precede each payload.
+    fn record_bytes(&self) -> usize {
+        if self.entry_records {
+            ENTRY_RECORD_SIZE
+        } else {
+            0
+        }
+    }
+}
 
 /// One mapped data chunk.
 struct SharedChunk {
     ptr: NonNull<u8>,
     total_size: usize, // header + data
+    layout: ChunkLayout,
     #[cfg(unix)]
     fd: std::os::unix::io::RawFd,
     _map_ptr: *mut u8,
@@ -149,38 +696,181 @@ unsafe impl Sync for SharedChunk {}
 impl SharedChunk {
     // ── Header field accessors ────────────────────────────────────────
 
-    fn used(&self) -> &AtomicU32 {
-        unsafe { &*(self.ptr.as_ptr() as *const AtomicU32) }
+    fn u32_at(&self, offset: usize) -> &AtomicU32 {
+        unsafe { &*(self.ptr.as_ptr().add(offset) as *const AtomicU32) }
+    }
+
+    fn u64_at(&self, offset: usize) -> &AtomicU64 {
+        unsafe { &*(self.ptr.as_ptr().add(offset) as *const AtomicU64) }
+    }
+
+    /// Bytes written so far (widened to u64 for v1 chunks).
+    fn used(&self) -> u64 {
+        if self.layout.wide {
+            self.u64_at(self.layout.used).load(Ordering::Acquire)
+        } else {
+            self.u32_at(self.layout.used).load(Ordering::Acquire) as u64
+        }
+    }
+
+    /// CAS `used` from `current` to `new`. v1 chunks never exceed 4 GB,
+    /// so `new` always fits in the 32-bit field.
+    fn cas_used(&self, current: u64, new: u64) -> std::result::Result<u64, u64> {
+        if self.layout.wide {
+            self.u64_at(self.layout.used).compare_exchange_weak(
+                current,
+                new,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+        } else {
+            self.u32_at(self.layout.used)
+                .compare_exchange_weak(
+                    current as u32,
+                    new as u32,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                )
+                .map(|v| v as u64)
+                .map_err(|v| v as u64)
+        }
     }
 
-    fn generation(&self) -> &AtomicU32 {
-        unsafe { &*(self.ptr.as_ptr().add(4) as *const AtomicU32) }
+    /// Recycling generation (widened to u64 for v1 chunks).
+    fn generation(&self) -> u64 {
+        if self.layout.wide {
+            self.u64_at(self.layout.generation).load(Ordering::Acquire)
+        } else {
+            self.u32_at(self.layout.generation).load(Ordering::Acquire) as u64
+        }
     }
 
     fn entry_count(&self) -> &AtomicU32 {
-        unsafe { &*(self.ptr.as_ptr().add(8) as *const AtomicU32) }
+        self.u32_at(self.layout.entry_count)
     }
 
     fn ack_count(&self) -> &AtomicU32 {
-        unsafe { &*(self.ptr.as_ptr().add(12) as *const AtomicU32) }
+        self.u32_at(self.layout.ack_count)
     }
 
     fn empty_since(&self) -> &AtomicU64 {
-        unsafe { &*(self.ptr.as_ptr().add(16) as *const AtomicU64) }
+        self.u64_at(self.layout.empty_since)
     }
 
     fn first_write_ts(&self) -> &AtomicU64 {
-        unsafe { &*(self.ptr.as_ptr().add(24) as *const AtomicU64) }
+        self.u64_at(self.layout.first_write_ts)
+    }
+
+    fn last_write_ts(&self) -> &AtomicU64 {
+        self.u64_at(self.layout.last_write_ts)
+    }
+
+    fn max_expires_at(&self) -> &AtomicU64 {
+        self.u64_at(self.layout.max_expires_at)
+    }
+
+    /// True once every entry is past its TTL: the newest entry on the
+    /// shared TTL is older than `ttl_ms` and no entry's own expiry is ahead.
+    fn ttl_expired(&self, ts: u64, ttl_ms: u64) -> bool {
+        let last = self.last_write_ts().load(Ordering::Acquire);
+        let (newest, own_expiry) = if self.layout.entry_records {
+            (last, self.max_expires_at().load(Ordering::Acquire))
+        } else {
+            // Libraries that predate `last_write_ts` leave it 0, so
+            // `first_write_ts` stands in. They cannot attach to arenas with
+            // entry records, where own-expiry entries stamp only the former.
+            (last.max(self.first_write_ts().load(Ordering::Acquire)), 0)
+        };
+        if newest == 0 && own_expiry == 0 {
+            return false;
+        }
+        (newest == 0 || ts.saturating_sub(newest) > ttl_ms) && ts > own_expiry
+    }
+
+    /// Header-relative position of the entry record in front of the
+    /// payload at data offset `offset`, or `None` if the arena has no
+    /// records or no entry can start there.
+    fn record_at(&self, offset: u64) -> Option<usize> {
+        let start = (offset as usize).checked_sub(ENTRY_RECORD_SIZE)?;
+        (self.layout.entry_records && start % 8 == 0).then_some(self.layout.header_size + start)
+    }
+
+    fn record_size(&self, record: usize) -> &AtomicU32 {
+        self.u32_at(record + RECORD_SIZE)
+    }
+
+    fn record_expires_at(&self, record: usize) -> &AtomicU64 {
+        self.u64_at(record + RECORD_EXPIRES_AT)
+    }
+
+    fn record_acks(&self, record: usize) -> &AtomicU32 {
+        self.u32_at(record + RECORD_ACKS)
     }
 
-    /// Pointer to the start of the data region (after the 64-byte header).
+    fn record_required_acks(&self, record: usize) -> &AtomicU32 {
+        self.u32_at(record + RECORD_REQUIRED_ACKS)
+    }
+
+    fn record_refs(&self, record: usize) -> &AtomicU32 {
+        self.u32_at(record + RECORD_REFS)
+    }
+
+    fn record_claim(&self, record: usize) -> &AtomicU64 {
+        self.u64_at(record + RECORD_CLAIM)
+    }
+
+    fn record_written_at(&self, record: usize) -> &AtomicU64 {
+        self.u64_at(record + RECORD_WRITTEN_AT)
+    }
+
+    /// Call `f(record, offset, size)` for the entries of this chunk in
+    /// offset order. Stops at the first record that cannot be complete,
+    /// such as one whose append is still in flight; `f` must not trust a
+    /// record beyond what it can check.
+    ///
+    /// Time: O(e) where e = entries in the chunk.
+    fn for_each_record(&self, mut f: impl FnMut(usize, u64, u32)) {
+        let used = self.used().min(self.data_capacity() as u64) as usize;
+        let entries = self.entry_count().load(Ordering::Acquire);
+        let mut start = 0;
+        for _ in 0..entries {
+            if start + ENTRY_RECORD_SIZE > used {
+                break;
+            }
+            let at = self.layout.header_size + start;
+            let size = self.record_size(at).load(Ordering::Acquire) as usize;
+            let end = start + ENTRY_RECORD_SIZE + size;
+            if size == 0 || end > used {
+                break;
+            }
+            f(at, (start + ENTRY_RECORD_SIZE) as u64, size as u32);
+            start = end.next_multiple_of(8);
+        }
+    }
+
+    /// Offset and size of the entry whose payload covers the `size` bytes
+    /// at `offset`, if any.
+    ///
+    /// Time: O(e) where e = entries in the chunk.
+    fn entry_covering(&self, offset: u64, size: u32) -> Option<(u64, u32)> {
+        let end = offset + size as u64;
+        let mut found = None;
+        self.for_each_record(|_, start, len| {
+            if start <= offset && end <= start + len as u64 {
+                found = Some((start, len));
+            }
+        });
+        found
+    }
+
+    /// Pointer to the start of the data region (after the header).
     fn data_ptr(&self) -> *mut u8 {
-        unsafe { self.ptr.as_ptr().add(CHUNK_HEADER_SIZE) }
+        unsafe { self.ptr.as_ptr().add(self.layout.header_size) }
     }
 
     /// Usable data capacity (total - header).
     fn data_capacity(&self) -> usize {
-        self.total_size - CHUNK_HEADER_SIZE
+        self.total_size - self.layout.header_size
     }
 
     // ── Init (creator only) ──────────────────────────────────────────
@@ -191,9 +881,15 @@ impl SharedChunk {
     /// Caller must ensure no other thread is concurrently reading this
     /// chunk's header (i.e. this is only called during allocation, before
     /// the chunk is visible to readers).
-    unsafe fn init(&self, generation: u32) {
-        ptr::write_bytes(self.ptr.as_ptr(), 0, CHUNK_HEADER_SIZE);
-        self.generation().store(generation, Ordering::Release);
+    unsafe fn init(&self, generation: u64) {
+        ptr::write_bytes(self.ptr.as_ptr(), 0, self.layout.header_size);
+        if self.layout.wide {
+            self.u64_at(self.layout.generation)
+                .store(generation, Ordering::Release);
+        } else {
+            self.u32_at(self.layout.generation)
+                .store(generation as u32, Ordering::Release);
+        }
     }
 }
 
@@ -215,11 +911,19 @@ pub struct SharedBackend {
     chunks: parking_lot::RwLock<BTreeMap<u32, Arc<SharedChunk>>>,
     namespace: String,
     chunk_size: usize,
-    /// Maximum number of chunks allowed (`None` = unlimited).
-    max_chunks: Option<u32>,
-    /// Usage fraction (0.0–1.0) at which to proactively pre-allocate the next chunk.
-    /// Default: 0.8 (80%). Set to 1.0 to disable prefetch.
-    prefetch_threshold: f32,
+    /// Chunk-header layout negotiated from the control file's version.
+    layout: ChunkLayout,
+    /// Key for handle tags; `None` unless the arena authenticates handles.
+    handle_key: Option<[u64; 2]>,
+    /// Random id of this namespace instance (0 = legacy arena, unchecked).
+    instance_id: u64,
+    /// Last `(write_head, entry_count, first_seen_ms)` observed by
+    /// `cleanup_chunks` while the head was fully acked; tracks how long
+    /// the head has been idle.
+    idle_head: parking_lot::Mutex<Option<(u32, u32, u64)>>,
+    /// Entries dropped unacknowledged by this process's sweeps, until
+    /// `take_dead_letters` (at most `DEAD_LETTER_CAPACITY`).
+    dead_letters: parking_lot::Mutex<Vec<DeadLetter>>,
     /// True if this process created the shared region (and is responsible for cleanup).
     is_creator: bool,
 }
@@ -247,16 +951,18 @@ impl SharedBackend {
 
     /// Validate that chunk_size is within acceptable bounds.
     ///
-    /// Must be > CHUNK_HEADER_SIZE (64) so there is usable data space,
-    /// and must fit in a u32 since the control file stores it as u32.
-    fn validate_chunk_size(chunk_size: usize) -> Result<()> {
-        if chunk_size < MIN_CHUNK_SIZE {
+    /// Must exceed the chunk header (64 bytes, or 192 with split counters)
+    /// so there is usable data space.
+    /// Layout v1 stores it as u32, so v1 arenas are capped at 4 GB.
+    fn validate_chunk_size(chunk_size: usize, layout: &ArenaLayout) -> Result<()> {
+        let min = ChunkLayout::for_arena(layout).header_size + 1;
+        if chunk_size < min {
             return Err(BlobError::DataTooLarge {
                 size: chunk_size,
-                max: MIN_CHUNK_SIZE,
+                max: min,
             });
         }
-        if chunk_size > u32::MAX as usize {
+        if layout.version == CTRL_VERSION_V1 && chunk_size > u32::MAX as usize {
             return Err(BlobError::DataTooLarge {
                 size: chunk_size,
                 max: u32::MAX as usize,
@@ -277,47 +983,107 @@ impl SharedBackend {
     /// Time: O(1) — two `shm_open` + `mmap` syscalls (ctrl + chunk 0).
     #[cfg(unix)]
     pub fn create(namespace: &str, chunk_size: usize, max_chunks: Option<u32>) -> Result<Self> {
+        let config = SharedConfig {
+            max_chunks,
+            ..SharedConfig::default()
+        };
+        Self::create_with_config(namespace, chunk_size, &config)
+    }
+
+    /// Create a new shared arena with an explicit shared policy.
+    ///
+    /// `config` is written into the control file and adopted by every
+    /// process that attaches later. `config.layout.version` selects the
+    /// on-disk format; pick `1` while v1-only processes still attach.
+    ///
+    /// Time: O(1) — two `shm_open` + `mmap` syscalls (ctrl + chunk 0).
+    #[cfg(unix)]
+    pub fn create_with_config(
+        namespace: &str,
+        chunk_size: usize,
+        config: &SharedConfig,
+    ) -> Result<Self> {
         Self::validate_namespace(namespace)?;
-        Self::validate_chunk_size(chunk_size)?;
-        let ctrl = Self::open_ctrl(namespace, true)?;
-        unsafe { ctrl.init(chunk_size as u32) };
+        config.validate(chunk_size as u64)?;
+        let version = config.layout.version;
+        Self::validate_chunk_size(chunk_size, &config.layout)?;
+        let handle_key = if config.layout.authenticated_handles {
+            Some(random_words::<2>()?)
+        } else {
+            None
+        };
+        // Nonzero so handles from this arena are always checked
+        let [instance_id] = random_words::<1>()?;
+        let instance_id = instance_id.max(1);
+        let ctrl = Self::open_ctrl(namespace, true, ctrl_size(version))?;
+        unsafe { ctrl.init(chunk_size as u64, config, instance_id, handle_key) };
 
         let backend = Self {
             ctrl,
             chunks: parking_lot::RwLock::new(BTreeMap::new()),
             namespace: namespace.to_string(),
             chunk_size,
-            max_chunks,
-            prefetch_threshold: 0.8,
+            layout: ChunkLayout::for_arena(&config.layout),
+            handle_key,
+            instance_id,
+            idle_head: parking_lot::Mutex::new(None),
+            dead_letters: parking_lot::Mutex::new(Vec::new()),
             is_creator: true,
         };
 
-        // Allocate chunk 0 and set chunk_count = 1
+        // Allocate chunk 0 and set chunk_count = live_chunks = 1
         backend.allocate_chunk(0)?;
         backend.ctrl.chunk_count().store(1, Ordering::Release);
+        backend.ctrl.live_chunks().store(1, Ordering::Release);
         Ok(backend)
     }
 
     /// Attach to an existing shared arena (called by subsequent processes).
     ///
-    /// `max_chunks`: Optional upper bound — should match the creator's setting.
+    /// The layout version is negotiated from the control file (v1 and v2
+    /// are both attached read/write; anything else is `UnsupportedLayout`).
+    /// The shared policy (TTL, decay, limits) is read from the control file.
+    /// For arenas created by an older version without a stored policy,
+    /// `SharedConfig::default()` is published on first attach.
     ///
     /// Time: O(c) where c = existing chunk count — eagerly maps all chunks.
     #[cfg(unix)]
-    pub fn attach(namespace: &str, max_chunks: Option<u32>) -> Result<Self> {
+    pub fn attach(namespace: &str) -> Result<Self> {
         Self::validate_namespace(namespace)?;
-        let ctrl = Self::open_ctrl(namespace, false)?;
-        ctrl.validate()?;
 
-        let chunk_size = ctrl.chunk_size() as usize;
+        // Probe the v1-sized prefix (common to every version) to learn the
+        // version, then remap the full control file if it is larger.
+        let probe = Self::open_ctrl(namespace, false, CTRL_SIZE_V1)?;
+        probe.validate()?;
+        let version = probe.version();
+        let ctrl = if ctrl_size(version) == CTRL_SIZE_V1 {
+            probe
+        } else {
+            drop(probe);
+            Self::open_ctrl(namespace, false, ctrl_size(version))?
+        };
+        ctrl.adopt_config(&SharedConfig::default(), |count| {
+            Self::count_chunk_files(namespace, count)
+        });
+
+        let layout = ctrl.chunk_layout();
+        let ctrl_handle_key = ctrl.handle_key();
+        let instance_id = ctrl.instance_id();
+        let chunk_size = usize::try_from(ctrl.chunk_size()).map_err(|_| BlobError::OutOfMemory)?;
+        if chunk_size <= layout.header_size {
+            return Err(BlobError::InvalidHandle);
+        }
 
         let backend = Self {
             ctrl,
             chunks: parking_lot::RwLock::new(BTreeMap::new()),
             namespace: namespace.to_string(),
             chunk_size,
-            max_chunks,
-            prefetch_threshold: 0.8,
+            layout,
+            handle_key: ctrl_handle_key,
+            instance_id,
+            idle_head: parking_lot::Mutex::new(None),
+            dead_letters: parking_lot::Mutex::new(Vec::new()),
             is_creator: false,
         };
 
@@ -330,6 +1096,27 @@ impl SharedBackend {
         Ok(backend)
     }
 
+    /// How many of the chunk files with ids below `count` are present in
+    /// `/dev/shm`; cleanup has unlinked the others.
+    ///
+    /// Time: O(c) — one `shm_open` per chunk id.
+    #[cfg(unix)]
+    fn count_chunk_files(namespace: &str, count: u32) -> u32 {
+        (0..count)
+            .filter(|id| {
+                let Ok(name) = CString::new(format!("/{}_data_{}", namespace, id)) else {
+                    return false;
+                };
+                let fd = unsafe { libc::shm_open(name.as_ptr(), libc::O_RDONLY, 0o600) };
+                if fd < 0 {
+                    return false;
+                }
+                unsafe { libc::close(fd) };
+                true
+            })
+            .count() as u32
+    }
+
     /// Unlink all `/dev/shm` files for a given namespace.
     ///
     /// Call this at application startup to clean up after a previous crash
@@ -350,7 +1137,7 @@ impl SharedBackend {
                 let map_ptr = unsafe {
                     libc::mmap(
                         ptr::null_mut(),
-                        CTRL_SIZE,
+                        CTRL_SIZE_V1,
                         libc::PROT_READ,
                         libc::MAP_SHARED,
                         fd,
@@ -363,7 +1150,7 @@ impl SharedBackend {
                         (p.add(20) as *const u32).read_volatile()
                     };
                     unsafe {
-                        libc::munmap(map_ptr, CTRL_SIZE);
+                        libc::munmap(map_ptr, CTRL_SIZE_V1);
                     }
                     count
                 } else {
@@ -409,27 +1196,164 @@ impl SharedBackend {
     /// when the active chunk is full and a recycled chunk must be found
     /// (scans all mapped chunks), or O(1) if a new chunk is allocated.
     pub fn append(&self, data: &[u8]) -> Result<OverflowHandle> {
-        if data.is_empty() {
+        self.append_entry(&[IoSlice::new(data)], 0, self.default_align())
+    }
+
+    /// Append data starting at an address that is a multiple of `align`
+    /// in every attached process, for consumers such as SIMD decoders or
+    /// `O_DIRECT` I/O. `align` must be a power of two up to
+    /// [`MAX_ALIGN`](crate::types::MAX_ALIGN): chunks are mapped at page
+    /// boundaries, so offsets aligned to that agree across processes.
+    /// `SharedConfig::align` sets the alignment of the other appends.
+    ///
+    /// The padding is claimed along with the entry and stays unused until
+    /// the chunk is freed. In arenas with entry records, padding in front
+    /// of a record is closed with a deleted filler entry of at least 40
+    /// bytes, so the records can still be walked in order.
+    ///
+    /// Errors: `InvalidAlignment` for any other `align`, otherwise as for
+    /// [`append`](Self::append), with room for the padding an empty chunk
+    /// needs.
+    ///
+    /// Time: as for `append`.
+    pub fn append_aligned(&self, data: &[u8], align: usize) -> Result<OverflowHandle> {
+        check_align(align)?;
+        self.append_entry(&[IoSlice::new(data)], 0, align)
+    }
+
+    /// Append the concatenation of `bufs` as a single entry, e.g. a header
+    /// and a body kept in separate buffers. The space is reserved with one
+    /// CAS and each slice is copied straight into place, so the entry and
+    /// handle are the same as for one `append` of the joined bytes.
+    ///
+    /// Errors: as for [`append`](Self::append), applied to the total length.
+    ///
+    /// Time: as for `append`, plus O(bufs.len()).
+    pub fn append_vectored(&self, bufs: &[IoSlice<'_>]) -> Result<OverflowHandle> {
+        self.append_entry(bufs, 0, self.default_align())
+    }
+
+    /// Append one `T` as its raw bytes, aligned for `T` (or to
+    /// `SharedConfig::align`, if larger), so [`view`](Self::view) can
+    /// borrow it in place in any attached process.
+    ///
+    /// Errors: `InvalidAlignment` if `T` needs more than
+    /// [`MAX_ALIGN`](crate::types::MAX_ALIGN), otherwise as for
+    /// [`append`](Self::append).
+    ///
+    /// Time: as for `append`.
+    pub fn append_value<T: Pod>(&self, value: &T) -> Result<Handle<T, OverflowHandle>> {
+        let align = align_of::<T>().max(self.default_align());
+        check_align(align)?;
+        self.append_entry(&[IoSlice::new(pod_bytes(value))], 0, align)
+            .map(Handle::new)
+    }
+
+    /// Append data that expires `ttl_ms` after now instead of after the
+    /// shared TTL. `resolve` and `cleanup_chunks` honour the entry's own
+    /// expiry, which may be shorter or longer than the shared TTL.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena was created with
+    /// `entry_records`; otherwise as for [`append`](Self::append).
+    ///
+    /// Time: as for `append`.
+    pub fn append_with_ttl(&self, data: &[u8], ttl_ms: u64) -> Result<OverflowHandle> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        // Never 0, which would mean "shared TTL"
+        let expires_at = now_ms().saturating_add(ttl_ms).max(1);
+        self.append_entry(&[IoSlice::new(data)], expires_at, self.default_align())
+    }
+
+    /// Shared body of the append variants. `data` is stored as if its
+    /// slices were one buffer, at a multiple of `align`. `expires_at` is
+    /// the entry's own expiry (ms since epoch), or 0 for the shared TTL;
+    /// only arenas with entry records can store a nonzero one.
+    fn append_entry(
+        &self,
+        data: &[IoSlice<'_>],
+        expires_at: u64,
+        align: usize,
+    ) -> Result<OverflowHandle> {
+        let len = gathered_len(data);
+        let (_, handle) = self.place_entry(len, expires_at, align, |dst| unsafe {
+            copy_gathered(data, 0, dst, len);
+        })?;
+        Ok(handle)
+    }
+
+    /// Alignment of appends that do not ask for one, from the shared policy.
+    fn default_align(&self) -> usize {
+        self.ctrl.align().load(Ordering::Relaxed).max(1) as usize
+    }
+
+    /// Largest payload one entry aligned to `align` can hold: a chunk's
+    /// data region, less the entry's record and padding.
+    fn max_entry_len(&self, align: usize) -> usize {
+        let (offset, _) = self.entry_span(0, 0, align);
+        let room = if self.layout.entry_records {
+            self.data_capacity() & !7
+        } else {
+            self.data_capacity()
+        };
+        room.saturating_sub(offset as usize).min(u32::MAX as usize)
+    }
+
+    /// Where the payload of a `len`-byte entry claimed at `used` starts, so
+    /// that its address is a multiple of `align`, and where the claim
+    /// ends. Chunks are mapped at page boundaries, so aligning the offset
+    /// from the start of the chunk aligns the address in every process.
+    ///
+    /// With entry records the payload follows its record, and the claim
+    /// ends 8-byte aligned. The gap in front of the record is either empty
+    /// or room for a filler record with a nonzero size.
+    fn entry_span(&self, used: u64, len: usize, align: usize) -> (u64, u64) {
+        let header = self.layout.header_size as u64;
+        let record = self.layout.record_bytes() as u64;
+        let align = align as u64;
+        let mut offset = (header + used + record).next_multiple_of(align) - header;
+        if record == 0 {
+            return (offset, offset + len as u64);
+        }
+        while offset - record > used && offset - record - used < FILLER_MIN_SIZE {
+            offset += align;
+        }
+        (offset, (offset + len as u64).next_multiple_of(8))
+    }
+
+    /// Claim room for a `len`-byte entry at a multiple of `align` in the
+    /// write head, set up its record, and let `write` fill the payload at
+    /// the pointer it is given. Returns the chunk along with the entry's
+    /// handle.
+    fn place_entry(
+        &self,
+        len: usize,
+        expires_at: u64,
+        align: usize,
+        write: impl FnOnce(*mut u8),
+    ) -> Result<(Arc<SharedChunk>, OverflowHandle)> {
+        let record = self.layout.record_bytes();
+        if len == 0 {
             return Err(BlobError::DataTooLarge {
                 size: 0,
                 max: self.data_capacity(),
             });
         }
-        if data.len() > self.data_capacity() {
-            return Err(BlobError::DataTooLarge {
-                size: data.len(),
-                max: self.data_capacity(),
-            });
+        let max = self.max_entry_len(align);
+        if len > max {
+            return Err(BlobError::DataTooLarge { size: len, max });
         }
 
         loop {
             let page_id = self.ctrl.write_head().load(Ordering::Acquire);
             let chunk = self.get_or_map_chunk(page_id)?;
 
-            let current_used = chunk.used().load(Ordering::Acquire);
-            let new_used = current_used as usize + data.len();
+            let current_used = chunk.used();
+            // Records are read atomically, so every entry starts 8-byte aligned.
+            let (offset, new_used) = self.entry_span(current_used, len, align);
 
-            if new_used > chunk.data_capacity() {
+            if new_used > chunk.data_capacity() as u64 {
                 // Chunk full — try to advance to a new chunk
                 let next_id = self.allocate_next_chunk(page_id)?;
                 let _ = self.ctrl.write_head().compare_exchange(
@@ -441,41 +1365,80 @@ impl SharedBackend {
                 continue;
             }
 
-            match chunk.used().compare_exchange_weak(
-                current_used,
-                new_used as u32,
-                Ordering::AcqRel,
-                Ordering::Acquire,
-            ) {
-                Ok(offset) => {
-                    // We own [offset, offset + data.len()) in the data region.
-                    unsafe {
-                        ptr::copy_nonoverlapping(
-                            data.as_ptr(),
-                            chunk.data_ptr().add(offset as usize),
-                            data.len(),
+            match chunk.cas_used(current_used, new_used) {
+                Ok(start) => {
+                    // We own [start, new_used) in the data region.
+                    let filler = record != 0 && offset - (record as u64) > start;
+                    if filler {
+                        if let Some(at) = chunk.record_at(start + record as u64) {
+                            Self::write_filler(&chunk, at, offset - (record as u64) - start);
+                        }
+                    }
+                    let mut handle =
+                        OverflowHandle::new(page_id, offset, len as u32, chunk.generation());
+                    if let Some(at) = chunk.record_at(offset) {
+                        chunk.record_size(at).store(len as u32, Ordering::Relaxed);
+                        chunk
+                            .record_expires_at(at)
+                            .store(expires_at, Ordering::Relaxed);
+                        let groups = self.ctrl.groups_mask().load(Ordering::Acquire);
+                        let required = if groups == 0 { ACK_UNGROUPED } else { groups };
+                        chunk
+                            .record_required_acks(at)
+                            .store(required, Ordering::Relaxed);
+                        chunk.record_acks(at).store(0, Ordering::Relaxed);
+                        chunk.record_refs(at).store(1, Ordering::Relaxed);
+                        chunk.record_claim(at).store(0, Ordering::Relaxed);
+                        chunk
+                            .record_written_at(at)
+                            .store(handle.timestamp, Ordering::Relaxed);
+                    }
+                    write(unsafe { chunk.data_ptr().add(offset as usize) });
+
+                    if filler {
+                        // The filler counts as an entry, acknowledged at once
+                        chunk.entry_count().fetch_add(2, Ordering::Release);
+                        chunk.ack_count().fetch_add(1, Ordering::AcqRel);
+                    } else {
+                        chunk.entry_count().fetch_add(1, Ordering::Release);
+                    }
+                    // Stamp first-write timestamp (CAS so only the first writer sets it).
+                    // The plain load first keeps later appends from taking the
+                    // line exclusive just to fail the CAS.
+                    if chunk.first_write_ts().load(Ordering::Relaxed) == 0 {
+                        let _ = chunk.first_write_ts().compare_exchange(
+                            0,
+                            handle.timestamp,
+                            Ordering::AcqRel,
+                            Ordering::Relaxed,
                         );
                     }
-
-                    chunk.entry_count().fetch_add(1, Ordering::Release);
-                    // Stamp first-write timestamp (CAS so only the first writer sets it)
-                    let _ = chunk.first_write_ts().compare_exchange(
-                        0,
-                        now_ms(),
-                        Ordering::AcqRel,
-                        Ordering::Relaxed,
-                    );
-                    let gen = chunk.generation().load(Ordering::Acquire);
+                    // An entry on the shared TTL advances `last_write_ts`; one
+                    // with its own expiry advances `max_expires_at` instead.
+                    // Likewise, appends that would not raise either skip the RMW.
+                    // A reservation counts as a write; its commit stamps again.
+                    let (stamp, value) = if expires_at == 0 || expires_at == EXPIRES_PENDING {
+                        (chunk.last_write_ts(), handle.timestamp)
+                    } else {
+                        (chunk.max_expires_at(), expires_at)
+                    };
+                    if stamp.load(Ordering::Relaxed) < value {
+                        stamp.fetch_max(value, Ordering::AcqRel);
+                    }
 
                     // Proactive prefetch: if this write pushed past the threshold,
                     // pre-allocate the next chunk so the next writer that overflows
                     // finds it already mapped (avoids shm_open latency spike).
                     let usage = new_used as f32 / chunk.data_capacity() as f32;
-                    if usage >= self.prefetch_threshold {
+                    let threshold =
+                        f32::from_bits(self.ctrl.prefetch_threshold().load(Ordering::Relaxed));
+                    if usage >= threshold {
                         let _ = self.allocate_next_chunk(page_id);
                     }
 
-                    return Ok(OverflowHandle::new(page_id, offset, data.len() as u32, gen));
+                    handle.tag = self.handle_tag(&handle);
+                    handle.instance_id = self.instance_id;
+                    return Ok((chunk, handle));
                 }
                 Err(_) => {
                     std::hint::spin_loop();
@@ -485,74 +1448,989 @@ impl SharedBackend {
         }
     }
 
-    /// Resolve an `OverflowHandle` to an owned copy of the data.
+    /// Fill the `gap` bytes from `record` on with a filler entry: deleted,
+    /// acknowledged by everyone and without references.
+    fn write_filler(chunk: &SharedChunk, record: usize, gap: u64) {
+        chunk
+            .record_size(record)
+            .store((gap - ENTRY_RECORD_SIZE as u64) as u32, Ordering::Relaxed);
+        chunk
+            .record_expires_at(record)
+            .store(EXPIRES_DELETED, Ordering::Relaxed);
+        chunk
+            .record_required_acks(record)
+            .store(ACK_UNGROUPED, Ordering::Relaxed);
+        chunk.record_acks(record).store(u32::MAX, Ordering::Relaxed);
+        chunk.record_refs(record).store(0, Ordering::Relaxed);
+        chunk.record_claim(record).store(0, Ordering::Relaxed);
+        chunk.record_written_at(record).store(0, Ordering::Relaxed);
+    }
+
+    /// Append data, letting the backend choose where it lives.
     ///
-    /// Returns `None` if:
-    /// - The chunk has been recycled (generation mismatch)
-    /// - The handle references out-of-bounds data
-    /// - The TTL has expired
+    /// Payloads of 1..=[`INLINE_CAPACITY`](crate::types::INLINE_CAPACITY) bytes are returned as inline
+    /// handles without touching shared memory; anything larger goes
+    /// through [`append`](Self::append). `resolve` and `acknowledge`
+    /// accept both kinds, so callers need not care which they got.
     ///
-    /// The data is copied out of the mmap region so it remains valid
-    /// even if the chunk is recycled after this call returns.
+    /// Time: O(1) for inline payloads, otherwise as for `append`.
+    pub fn append_auto(&self, data: &[u8]) -> Result<OverflowHandle> {
+        match OverflowHandle::inline(data) {
+            Some(mut handle) if !data.is_empty() => {
+                handle.instance_id = self.instance_id;
+                Ok(handle)
+            }
+            _ => self.append(data),
+        }
+    }
+
+    /// Resolve an `OverflowHandle` to an owned copy of the data.
+    ///
+    /// Returns `None` if:
+    /// - The handle was issued by another namespace instance
+    /// - The tag does not verify (authenticated arenas only)
+    /// - The chunk has been recycled (generation mismatch)
+    /// - The handle references data beyond the chunk's `used` watermark
+    /// - The TTL has expired (the entry's own TTL, if it was appended with one)
+    ///
+    /// Inline handles resolve to the bytes they carry, subject only to TTL.
+    /// Use [`try_resolve`](Self::try_resolve) to tell these cases apart.
     ///
     /// Time: O(d) where d = `handle.size` (memcpy cost). Chunk lookup is
     /// O(1) amortised (BTreeMap read under RwLock, lazily mapped).
     pub fn resolve(&self, handle: &OverflowHandle, ttl_ms: u64) -> Option<Vec<u8>> {
-        if handle.is_expired(ttl_ms) {
-            return None;
+        self.try_resolve(handle, ttl_ms).ok()
+    }
+
+    /// Resolve an `OverflowHandle`, reporting why it was rejected.
+    ///
+    /// Errors: `HandleExpired` past the TTL, `WrongInstance` for handles
+    /// from another instance of the namespace (e.g. one torn down and
+    /// recreated under the same name), `InvalidHandle` for everything else.
+    ///
+    /// The data is copied out of the mmap region so it remains valid
+    /// even if the chunk is recycled after this call returns.
+    ///
+    /// Time: O(d) where d = `handle.size` (memcpy cost).
+    pub fn try_resolve(&self, handle: &OverflowHandle, ttl_ms: u64) -> Result<Vec<u8>> {
+        if handle.is_inline() {
+            if handle.is_expired(ttl_ms) {
+                return Err(BlobError::HandleExpired);
+            }
+            return handle
+                .inline_data()
+                .map(<[u8]>::to_vec)
+                .ok_or(BlobError::InvalidHandle);
         }
+        let (chunk, entry) = self.readable_chunk(handle, ttl_ms)?;
+        let mut buf = vec![0u8; handle.size as usize];
+        Self::copy_entry(&chunk, &entry, handle, 0, &mut buf)?;
+        Ok(buf)
+    }
 
-        let chunk = self.get_or_map_chunk(handle.page_id).ok()?;
-        let gen = chunk.generation().load(Ordering::Acquire);
-        if gen != handle.generation {
-            return None;
+    /// Copy the bytes of the entry `handle` refers to, from `offset` on,
+    /// into `buf`, without copying the rest. Returns how many: less than
+    /// `buf.len()` only at the end of the entry, 0 at or past it.
+    ///
+    /// Errors: as for [`try_resolve`](Self::try_resolve), whatever the
+    /// range.
+    ///
+    /// Time: O(n) in the bytes copied.
+    pub fn read_at(
+        &self,
+        handle: &OverflowHandle,
+        ttl_ms: u64,
+        offset: u64,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        self.read_entry(handle, ttl_ms, offset, buf)
+    }
+
+    /// Like [`try_resolve`](Self::try_resolve), but into `buf`, replacing
+    /// its contents, so one buffer can be reused across reads. Returns the
+    /// entry's size.
+    ///
+    /// Errors: as for `try_resolve`. `buf` is left empty.
+    pub fn get_into(
+        &self,
+        handle: &OverflowHandle,
+        ttl_ms: u64,
+        buf: &mut Vec<u8>,
+    ) -> Result<usize> {
+        buf.clear();
+        if handle.is_inline() {
+            if handle.is_expired(ttl_ms) {
+                return Err(BlobError::HandleExpired);
+            }
+            let data = handle.inline_data().ok_or(BlobError::InvalidHandle)?;
+            buf.extend_from_slice(data);
+            return Ok(data.len());
         }
+        let (chunk, entry) = self.readable_chunk(handle, ttl_ms)?;
+        buf.resize(handle.size as usize, 0);
+        Self::copy_entry(&chunk, &entry, handle, 0, buf).inspect_err(|_| buf.clear())
+    }
 
-        let start = handle.offset as usize;
-        let end = start.checked_add(handle.size as usize)?;
-        if end > chunk.data_capacity() {
-            return None;
+    /// Open a reader over the entry `handle` refers to, for streaming it
+    /// without a full copy: [`SharedBlobReader`] implements `Read` and
+    /// `Seek`, and copies straight from the mapping into the caller's
+    /// buffer.
+    ///
+    /// Every read rechecks the handle as [`try_resolve`](Self::try_resolve)
+    /// does, so once the entry expires, is deleted or its chunk is recycled,
+    /// reads fail with an `io::Error` wrapping the same error instead of
+    /// returning other data.
+    ///
+    /// Errors: as for `try_resolve`, if the entry is not readable now.
+    pub fn open_reader(
+        &self,
+        handle: &OverflowHandle,
+        ttl_ms: u64,
+    ) -> Result<SharedBlobReader<'_>> {
+        self.read_entry(handle, ttl_ms, 0, &mut [])?;
+        Ok(SharedBlobReader {
+            backend: self,
+            handle: *handle,
+            ttl_ms,
+            pos: 0,
+        })
+    }
+
+    /// Handle to the bytes `range` of the entry `handle` refers to, like
+    /// [`OverflowHandle::slice`], but one that authenticated arenas accept:
+    /// once `handle` verifies, the slice gets a tag of its own.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// `InvalidHandle` if the range is empty or out of bounds, otherwise as
+    /// for `try_resolve` (apart from TTL) in authenticated arenas.
+    ///
+    /// Time: O(1), plus a walk of the chunk's records when `handle` is
+    /// itself a slice in an authenticated arena.
+    pub fn slice(
+        &self,
+        handle: &OverflowHandle,
+        range: impl RangeBounds<u64>,
+    ) -> Result<OverflowHandle> {
+        let mut slice = handle.slice(range).ok_or(BlobError::InvalidHandle)?;
+        if slice == *handle || slice.is_inline() {
+            return Ok(slice);
+        }
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        if self.handle_key.is_some() {
+            self.checked_entry(handle)?;
+            slice.tag = self.handle_tag(&slice);
+        }
+        Ok(slice)
+    }
+
+    /// Read the `T` that `handle` refers to, copied out of the mapping.
+    ///
+    /// Unlike a heap view this never borrows in place: a
+    /// [`purge`](Self::purge), here or in another process, may zero the
+    /// bytes at any time, and nothing can defer it while a reference is
+    /// held. The copy is taken once; it does not change afterwards.
+    ///
+    /// Errors: as for [`try_resolve`](Self::try_resolve).
+    ///
+    /// Time: O(size_of::<T>()).
+    pub fn view<T: Pod>(
+        &self,
+        handle: &Handle<T, OverflowHandle>,
+        ttl_ms: u64,
+    ) -> Result<SharedValueRef<T>> {
+        let mut value = pod_zeroed::<T>();
+        if self.read_entry(handle.raw(), ttl_ms, 0, pod_bytes_mut(&mut value))? != size_of::<T>() {
+            return Err(BlobError::InvalidHandle);
+        }
+        Ok(SharedValueRef { value })
+    }
+
+    /// Copy bytes of the entry `handle` refers to, from `pos` on, into
+    /// `dst`. Returns how many; 0 at or past the end. The checks are those
+    /// of `try_resolve`, made whatever the range.
+    fn read_entry(
+        &self,
+        handle: &OverflowHandle,
+        ttl_ms: u64,
+        pos: u64,
+        dst: &mut [u8],
+    ) -> Result<usize> {
+        if handle.is_inline() {
+            if handle.is_expired(ttl_ms) {
+                return Err(BlobError::HandleExpired);
+            }
+            let data = handle.inline_data().ok_or(BlobError::InvalidHandle)?;
+            let src = data.get(pos as usize..).unwrap_or_default();
+            let n = src.len().min(dst.len());
+            dst[..n].copy_from_slice(&src[..n]);
+            return Ok(n);
+        }
+        let (chunk, entry) = self.readable_chunk(handle, ttl_ms)?;
+        Self::copy_entry(&chunk, &entry, handle, pos, dst)
+    }
+
+    /// The chunk holding the entry `handle` refers to, if it can be read,
+    /// and the entry's handle, as for [`checked_entry`](Self::checked_entry).
+    /// Not for inline handles.
+    fn readable_chunk(
+        &self,
+        handle: &OverflowHandle,
+        ttl_ms: u64,
+    ) -> Result<(Arc<SharedChunk>, OverflowHandle)> {
+        // Without entry records the handle alone decides, so stale handles
+        // are rejected before any chunk is mapped.
+        if !self.layout.entry_records && handle.is_expired(ttl_ms) {
+            return Err(BlobError::HandleExpired);
+        }
+        let (chunk, entry) = self.checked_entry(handle)?;
+        if Self::entry_expired(&chunk, &entry, ttl_ms) {
+            return Err(BlobError::HandleExpired);
+        }
+        Ok((chunk, entry))
+    }
+
+    /// Copy the bytes of `handle`, part of `entry`, from `pos` on into
+    /// `dst`, then make sure the copy was not torn by recycling or a
+    /// delete. Returns how many.
+    fn copy_entry(
+        chunk: &SharedChunk,
+        entry: &OverflowHandle,
+        handle: &OverflowHandle,
+        pos: u64,
+        dst: &mut [u8],
+    ) -> Result<usize> {
+        let n = (handle.size as u64)
+            .saturating_sub(pos)
+            .min(dst.len() as u64) as usize;
+        if n == 0 {
+            return Ok(0);
         }
+        let start = (handle.offset + pos) as usize;
 
         // Copy data out so the caller is safe even if the chunk is recycled.
-        let mut buf = vec![0u8; handle.size as usize];
         unsafe {
-            ptr::copy_nonoverlapping(chunk.data_ptr().add(start), buf.as_mut_ptr(), buf.len());
+            ptr::copy_nonoverlapping(chunk.data_ptr().add(start), dst.as_mut_ptr(), n);
         }
 
         // Re-check generation after copy to detect concurrent recycling.
-        let gen_after = chunk.generation().load(Ordering::Acquire);
-        if gen_after != handle.generation {
-            return None;
+        if chunk.generation() != handle.generation {
+            return Err(BlobError::InvalidHandle);
+        }
+        // A delete that raced the copy may have zeroed part of it
+        if Self::is_deleted(chunk, entry) {
+            return Err(BlobError::HandleExpired);
         }
 
-        Some(buf)
+        Ok(n)
     }
 
     /// Acknowledge that an entry has been consumed.
     ///
+    /// Handles that fail the same checks as `resolve` (apart from TTL)
+    /// are ignored and return `false`. Acknowledging an inline handle is a
+    /// no-op that returns `true`.
+    ///
+    /// In arenas with entry records an entry is counted once however often
+    /// it is acknowledged, and a plain acknowledge releases it on behalf of
+    /// every consumer group (see [`acknowledge_group`](Self::acknowledge_group)).
+    /// Holders that [`retain`](Self::retain)ed it keep it alive until they
+    /// release it. Acknowledging a [slice](OverflowHandle::slice) is a
+    /// no-op: a retained slice is dropped with `release`.
+    ///
     /// Time: O(1) — atomic `fetch_add` on the chunk's ack counter.
     pub fn acknowledge(&self, handle: &OverflowHandle) -> bool {
-        if let Ok(chunk) = self.get_or_map_chunk(handle.page_id) {
-            let gen = chunk.generation().load(Ordering::Acquire);
-            if gen == handle.generation {
-                let prev_ack = chunk.ack_count().fetch_add(1, Ordering::AcqRel);
-                let entries = chunk.entry_count().load(Ordering::Acquire);
-                // If this ack completes all entries, record the timestamp
-                if prev_ack + 1 >= entries && entries > 0 {
-                    let ts = now_ms();
-                    // CAS to avoid overwriting if already set
-                    let _ = chunk.empty_since().compare_exchange(
-                        0,
-                        ts,
-                        Ordering::AcqRel,
-                        Ordering::Acquire,
-                    );
+        self.try_acknowledge(handle).is_ok()
+    }
+
+    /// Acknowledge an entry, reporting why the handle was rejected.
+    ///
+    /// Errors: `WrongInstance` or `InvalidHandle`, as for `try_resolve`.
+    ///
+    /// Time: O(1) — atomic `fetch_add` on the chunk's ack counter.
+    pub fn try_acknowledge(&self, handle: &OverflowHandle) -> Result<()> {
+        // Inline payloads occupy no shared memory
+        if handle.is_inline() {
+            return Ok(());
+        }
+        let (chunk, entry) = self.checked_entry(handle)?;
+        if (entry.offset, entry.size) != (handle.offset, handle.size) {
+            // A slice stands for its entry, which only its own handle acks
+            return Ok(());
+        }
+        if self.layout.entry_records {
+            return Self::ack_entry(&chunk, handle, u32::MAX);
+        }
+        Self::complete_entry(&chunk);
+        Ok(())
+    }
+
+    /// Count one more entry of `chunk` as acknowledged.
+    fn complete_entry(chunk: &SharedChunk) {
+        let prev_ack = chunk.ack_count().fetch_add(1, Ordering::AcqRel);
+        let entries = chunk.entry_count().load(Ordering::Acquire);
+        // If this ack completes all entries, record the timestamp
+        if prev_ack + 1 >= entries && entries > 0 {
+            let ts = now_ms();
+            // CAS to avoid overwriting if already set
+            let _ =
+                chunk
+                    .empty_since()
+                    .compare_exchange(0, ts, Ordering::AcqRel, Ordering::Acquire);
+        }
+    }
+
+    /// Add `bits` to the entry's acks, dropping its initial reference if
+    /// they now cover every ack it requires. Bits it does not require are
+    /// ignored.
+    fn ack_entry(chunk: &SharedChunk, handle: &OverflowHandle, bits: u32) -> Result<()> {
+        let at = chunk
+            .record_at(handle.offset)
+            .ok_or(BlobError::InvalidHandle)?;
+        let required = chunk.record_required_acks(at).load(Ordering::Acquire);
+        let bits = bits & required;
+        if bits == 0 {
+            return Ok(());
+        }
+        let prev = chunk.record_acks(at).fetch_or(bits, Ordering::AcqRel);
+        if prev & required != required && (prev | bits) & required == required {
+            // A holder may already have dropped the last reference
+            let _ = Self::release_entry(chunk, at);
+        }
+        Ok(())
+    }
+
+    /// Drop one reference to the entry whose record is at `record`,
+    /// completing the entry when it was the last.
+    fn release_entry(chunk: &SharedChunk, record: usize) -> Result<()> {
+        let prev = chunk
+            .record_refs(record)
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .map_err(|_| BlobError::InvalidHandle)?;
+        if prev == 1 {
+            Self::complete_entry(chunk);
+        }
+        Ok(())
+    }
+
+    // ── Reference counts ──────────────────────────────────────────────
+
+    /// Take another reference to an entry, for one more holder (in any
+    /// attached process) that will [`release`](Self::release) it.
+    ///
+    /// Every entry starts with one reference, which `acknowledge` (or
+    /// the acks of every consumer group) or `release` drops. Once the count
+    /// reaches zero the entry counts as acknowledged, its chunk can be
+    /// reclaimed, and it can no longer be retained. References do not
+    /// extend the TTL; holders that need longer should [`touch`](Self::touch)
+    /// the entry.
+    ///
+    /// A [slice](OverflowHandle::slice) stands for its whole entry: retain
+    /// one before handing it on, so the entry outlives every slice in use,
+    /// and release it once done; acknowledging a slice drops no reference.
+    /// Inline handles hold no shared memory; retaining one is a no-op.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// `HandleExpired` past the entry's TTL, `InvalidHandle` once its count
+    /// has reached zero, otherwise as for `try_resolve`.
+    ///
+    /// Time: O(1) — CAS loop on the entry record.
+    pub fn retain(&self, handle: &OverflowHandle) -> Result<()> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        if handle.is_inline() {
+            return Ok(());
+        }
+        let (chunk, entry) = self.checked_entry(handle)?;
+        if Self::entry_expired(&chunk, &entry, self.ctrl.ttl_ms().load(Ordering::Acquire)) {
+            return Err(BlobError::HandleExpired);
+        }
+        let at = chunk
+            .record_at(entry.offset)
+            .ok_or(BlobError::InvalidHandle)?;
+        chunk
+            .record_refs(at)
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n > 0).then(|| n.saturating_add(1))
+            })
+            .map(|_| ())
+            .map_err(|_| BlobError::InvalidHandle)
+    }
+
+    /// Drop a reference taken by `append` or [`retain`](Self::retain).
+    /// When the last one goes the entry counts as acknowledged.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// `InvalidHandle` if no reference is left, otherwise as for
+    /// `try_acknowledge`.
+    ///
+    /// Time: O(1) — CAS loop on the entry record.
+    pub fn release(&self, handle: &OverflowHandle) -> Result<()> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        if handle.is_inline() {
+            return Ok(());
+        }
+        let (chunk, entry) = self.checked_entry(handle)?;
+        let at = chunk
+            .record_at(entry.offset)
+            .ok_or(BlobError::InvalidHandle)?;
+        Self::release_entry(&chunk, at)
+    }
+
+    // ── Deletion ──────────────────────────────────────────────────────
+
+    /// Delete an entry, from any attached process: `resolve` fails for it
+    /// from now on (`HandleExpired`), and it counts as acknowledged for
+    /// reclamation even if references to it are still held or consumer
+    /// groups have not acked it. The bytes stay in the chunk until it is
+    /// unlinked; use [`purge`](Self::purge) to overwrite them.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// `InvalidHandle` for inline handles (their payload lives in the
+    /// handle itself), otherwise as for `try_acknowledge`.
+    ///
+    /// Time: O(1).
+    pub fn delete(&self, handle: &OverflowHandle) -> Result<()> {
+        self.delete_entry(handle, false)
+    }
+
+    /// Delete an entry like [`delete`](Self::delete) and overwrite its
+    /// payload with zeros, for data that must not linger in `/dev/shm`.
+    ///
+    /// Errors: as for `delete`.
+    ///
+    /// Time: O(size).
+    pub fn purge(&self, handle: &OverflowHandle) -> Result<()> {
+        self.delete_entry(handle, true)
+    }
+
+    /// Shared body of `delete` and `purge`.
+    fn delete_entry(&self, handle: &OverflowHandle, zero: bool) -> Result<()> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        if handle.is_inline() {
+            return Err(BlobError::InvalidHandle);
+        }
+        let chunk = self.checked_chunk(handle)?;
+        let at = chunk
+            .record_at(handle.offset)
+            .ok_or(BlobError::InvalidHandle)?;
+        // Mark it first, so readers that copy the zeros discard them
+        chunk
+            .record_expires_at(at)
+            .store(EXPIRES_DELETED, Ordering::Release);
+        if zero {
+            // SAFETY: `checked_chunk` verified the range lies below `used`.
+            // Chunk ids are never reused, so the mapping still holds this
+            // entry even if the chunk has been unlinked meanwhile.
+            unsafe {
+                ptr::write_bytes(
+                    chunk.data_ptr().add(handle.offset as usize),
+                    0,
+                    handle.size as usize,
+                );
+            }
+        }
+        Self::retire_entry(&chunk, at);
+        Ok(())
+    }
+
+    /// Count the entry whose record is at `record` as acknowledged by
+    /// everyone, whatever references are left.
+    fn retire_entry(chunk: &SharedChunk, record: usize) {
+        chunk
+            .record_acks(record)
+            .fetch_or(u32::MAX, Ordering::AcqRel);
+        // Drop every reference at once; the entry completes only if this
+        // took the last of them
+        if chunk.record_refs(record).swap(0, Ordering::AcqRel) > 0 {
+            Self::complete_entry(chunk);
+        }
+    }
+
+    // ── Reserve / commit ──────────────────────────────────────────────
+
+    /// Reserve room for a `len`-byte entry that the caller writes in
+    /// place, for producers that serialize straight into shared memory
+    /// instead of into a temporary buffer. Fill the returned slot, then
+    /// [`commit`](SharedWriteSlot::commit) it to get the handle.
+    ///
+    /// Until the commit the entry is pending and `resolve` treats it as
+    /// expired. A slot dropped without committing is deleted, so it never
+    /// becomes readable and counts as acknowledged. The reservation stamps
+    /// the chunk like an append: commit within the shared TTL, or a sweep
+    /// may free the chunk under the writer.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// otherwise as for [`append`](Self::append).
+    ///
+    /// Time: as for `append`, without the copy.
+    pub fn reserve(&self, len: usize) -> Result<SharedWriteSlot<'_>> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        // Chunks are never reused in place, so the payload is still zeroed
+        let (chunk, handle) =
+            self.place_entry(len, EXPIRES_PENDING, self.default_align(), |_| {})?;
+        Ok(SharedWriteSlot {
+            backend: self,
+            chunk,
+            handle,
+            committed: false,
+        })
+    }
+
+    /// Publish a pending entry on the shared TTL, counted from now.
+    fn commit_reserved(
+        &self,
+        chunk: &SharedChunk,
+        reserved: &OverflowHandle,
+    ) -> Result<OverflowHandle> {
+        let at = chunk
+            .record_at(reserved.offset)
+            .ok_or(BlobError::InvalidHandle)?;
+        let mut handle = OverflowHandle::new(
+            reserved.page_id,
+            reserved.offset,
+            reserved.size,
+            reserved.generation,
+        );
+        // Stamp before publishing, so a sweep never sees the entry live in
+        // a chunk that looks stale
+        chunk
+            .last_write_ts()
+            .fetch_max(handle.timestamp, Ordering::AcqRel);
+        chunk
+            .record_written_at(at)
+            .store(handle.timestamp, Ordering::Relaxed);
+        chunk
+            .record_expires_at(at)
+            .compare_exchange(EXPIRES_PENDING, 0, Ordering::AcqRel, Ordering::Acquire)
+            .map_err(|_| BlobError::InvalidHandle)?;
+        handle.tag = self.handle_tag(&handle);
+        handle.instance_id = self.instance_id;
+        Ok(handle)
+    }
+
+    /// Start an entry of unknown length, written through `std::io::Write`
+    /// as its bytes arrive; [`finish`](SharedBlobWriter::finish) returns
+    /// its handle. A writer dropped unfinished deletes what it wrote, so it
+    /// counts as acknowledged.
+    ///
+    /// The first write reserves the largest entry a chunk holds, which
+    /// takes a chunk of its own, and the bytes are written there in place.
+    /// `finish` cuts the entry down to what was written and hands the rest
+    /// of the chunk back, so nothing is copied. Pages of the chunk that
+    /// were never written are never touched, so they take no memory
+    /// meanwhile.
+    ///
+    /// An entry never spans chunks, so a stream is capped at the largest
+    /// entry a chunk holds: a little under the chunk size (32 MB by
+    /// default). A `write` that would cross the cap writes nothing and
+    /// fails with `DataTooLarge`; what was written before can still be
+    /// finished.
+    ///
+    /// Errors (from `write`, as `io::Error`s wrapping a `BlobError`):
+    /// `DataTooLarge` past the largest entry, otherwise as for
+    /// [`reserve`](Self::reserve). `LayoutFeatureRequired` from here
+    /// unless the arena has entry records.
+    pub fn writer(&self) -> Result<SharedBlobWriter<'_>> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        Ok(SharedBlobWriter {
+            backend: self,
+            slot: None,
+            len: 0,
+        })
+    }
+
+    /// Cut a pending entry down to its first `len` bytes, nonzero, and
+    /// hand the rest of its claim back to the chunk if nothing was placed
+    /// after it. Returns the shortened handle.
+    fn shrink_reserved(
+        chunk: &SharedChunk,
+        reserved: &OverflowHandle,
+        len: usize,
+    ) -> Result<OverflowHandle> {
+        let at = chunk
+            .record_at(reserved.offset)
+            .ok_or(BlobError::InvalidHandle)?;
+        if chunk.record_expires_at(at).load(Ordering::Acquire) != EXPIRES_PENDING {
+            return Err(BlobError::InvalidHandle);
+        }
+        let end = (reserved.offset + reserved.size as u64).next_multiple_of(8);
+        let new_end = (reserved.offset + len as u64).next_multiple_of(8);
+        // Shrink the record first: a walk that sees the old `used` then
+        // stops at the zeroed record after it
+        chunk.record_size(at).store(len as u32, Ordering::Release);
+        while let Err(used) = chunk.cas_used(end, new_end) {
+            if used != end {
+                // Something was placed after the entry; the gap stays empty
+                break;
+            }
+        }
+        let mut handle = *reserved;
+        handle.size = len as u32;
+        Ok(handle)
+    }
+
+    /// Delete a pending entry whose slot was dropped uncommitted.
+    fn abandon_reserved(chunk: &SharedChunk, reserved: &OverflowHandle) {
+        let Some(at) = chunk.record_at(reserved.offset) else {
+            return;
+        };
+        if chunk
+            .record_expires_at(at)
+            .compare_exchange(
+                EXPIRES_PENDING,
+                EXPIRES_DELETED,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_ok()
+        {
+            Self::retire_entry(chunk, at);
+        }
+    }
+
+    // ── Claims ────────────────────────────────────────────────────────
+
+    /// Mark an entry as in flight with `consumer` for the next
+    /// `visibility_timeout_ms`, so competing consumers that also claim it
+    /// back off. Claiming it again as the same consumer moves the deadline
+    /// (a timeout of 0 gives it up). Once the deadline passes without the
+    /// entry being acknowledged, any consumer may claim it and
+    /// [`take_expired_claims`](Self::take_expired_claims) hands it out for
+    /// redelivery.
+    ///
+    /// Claims are advisory: they do not stop anyone from resolving or
+    /// acknowledging the entry, and do not extend its TTL.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// `AlreadyClaimed` while another consumer's claim is live,
+    /// `HandleExpired` past the entry's TTL, `InvalidHandle` for inline
+    /// handles and entries already acknowledged or released, otherwise as
+    /// for `try_resolve`.
+    ///
+    /// Time: O(1) — CAS loop on the entry record.
+    pub fn claim(
+        &self,
+        handle: &OverflowHandle,
+        consumer: u16,
+        visibility_timeout_ms: u64,
+    ) -> Result<()> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        if handle.is_inline() {
+            return Err(BlobError::InvalidHandle);
+        }
+        let chunk = self.checked_chunk(handle)?;
+        let ttl_ms = self.ctrl.ttl_ms().load(Ordering::Acquire);
+        if Self::entry_expired(&chunk, handle, ttl_ms) {
+            return Err(BlobError::HandleExpired);
+        }
+        let at = chunk
+            .record_at(handle.offset)
+            .ok_or(BlobError::InvalidHandle)?;
+        if chunk.record_refs(at).load(Ordering::Acquire) == 0 {
+            return Err(BlobError::InvalidHandle);
+        }
+        let expires_at = handle.timestamp.saturating_add(ttl_ms).max(1);
+        if chunk
+            .record_expires_at(at)
+            .compare_exchange(0, expires_at, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+        {
+            chunk
+                .max_expires_at()
+                .fetch_max(expires_at, Ordering::AcqRel);
+        }
+
+        let ts = now_ms();
+        // A timeout of 0 leaves a deadline that has already passed, so the
+        // claim lapses within the current millisecond too
+        let deadline = if visibility_timeout_ms == 0 {
+            ts.saturating_sub(1)
+        } else {
+            ts.saturating_add(visibility_timeout_ms)
+                .min(CLAIM_DEADLINE_MASK)
+        };
+        let claim = (consumer as u64) << CLAIM_DEADLINE_BITS | deadline;
+        chunk
+            .record_claim(at)
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                let owner = (current >> CLAIM_DEADLINE_BITS) as u16;
+                let lapsed = current & CLAIM_DEADLINE_MASK < ts;
+                (current == 0 || lapsed || owner == consumer).then_some(claim)
+            })
+            .map(|_| ())
+            .map_err(|current| BlobError::AlreadyClaimed {
+                owner: (current >> CLAIM_DEADLINE_BITS) as u16,
+            })
+    }
+
+    /// Collect the entries whose claim has lapsed while they are still
+    /// live (not fully acknowledged and within their TTL), clearing each
+    /// claim so the entry is handed out once. The handles are rebuilt from
+    /// the arena and resolve like the originals.
+    ///
+    /// Run periodically by one process (the lifecycle manager does); with
+    /// several, each lapsed claim still goes to exactly one of them. Only
+    /// chunks this process has mapped are scanned.
+    ///
+    /// Returns an empty list in arenas without entry records.
+    ///
+    /// Time: O(e) where e = entries in chunks with unacknowledged entries.
+    pub fn take_expired_claims(&self) -> Vec<OverflowHandle> {
+        if !self.layout.entry_records {
+            return Vec::new();
+        }
+        let chunks: Vec<(u32, Arc<SharedChunk>)> = self
+            .chunks
+            .read()
+            .iter()
+            .map(|(&id, chunk)| (id, Arc::clone(chunk)))
+            .collect();
+        let ts = now_ms();
+        let mut lapsed = Vec::new();
+        for (id, chunk) in chunks {
+            if chunk.ack_count().load(Ordering::Acquire)
+                >= chunk.entry_count().load(Ordering::Acquire)
+            {
+                continue;
+            }
+            let generation = chunk.generation();
+            let found = lapsed.len();
+            chunk.for_each_record(|at, offset, size| {
+                let claim = chunk.record_claim(at).load(Ordering::Acquire);
+                // Claimed records always carry their own expiry, so stale
+                // records from before a recycle fail one of these checks.
+                if claim == 0
+                    || claim & CLAIM_DEADLINE_MASK >= ts
+                    || chunk.record_refs(at).load(Ordering::Acquire) == 0
+                    || ts > chunk.record_expires_at(at).load(Ordering::Acquire)
+                {
+                    return;
                 }
-                return true;
+                if chunk
+                    .record_claim(at)
+                    .compare_exchange(claim, 0, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+                {
+                    let mut handle = OverflowHandle::new(id, offset, size, generation);
+                    handle.tag = self.handle_tag(&handle);
+                    handle.instance_id = self.instance_id;
+                    lapsed.push(handle);
+                }
+            });
+            // Recycled while we walked it: nothing found there is valid
+            if chunk.generation() != generation {
+                lapsed.truncate(found);
             }
         }
-        false
+        lapsed
+    }
+
+    // ── Consumer groups ───────────────────────────────────────────────
+
+    /// Register a consumer group, or look up its id if any attached
+    /// process already registered `name`.
+    ///
+    /// Each group acknowledges entries independently through
+    /// [`acknowledge_group`](Self::acknowledge_group). An entry, and so its
+    /// chunk, is only released once every group registered when it was
+    /// appended has acknowledged it (or by TTL). Entries appended before a
+    /// group registered need no ack from it. Groups cannot be removed; a
+    /// group that stops acknowledging holds data until the TTL.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// `InvalidConfig` for names that are empty or longer than
+    /// [`MAX_GROUP_NAME_LEN`] bytes, `TooManyGroups` once [`MAX_GROUPS`]
+    /// groups exist.
+    ///
+    /// Time: O(g) where g = registered groups.
+    pub fn register_group(&self, name: &str) -> Result<u32> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        if name.is_empty() || name.len() > MAX_GROUP_NAME_LEN {
+            return Err(BlobError::InvalidConfig(
+                "consumer group name must be 1 to 24 bytes",
+            ));
+        }
+        self.ctrl.register_group(name.as_bytes())
+    }
+
+    /// Acknowledge an entry on behalf of consumer group `group`.
+    ///
+    /// Returns `false` for rejected handles and unknown groups; see
+    /// [`try_acknowledge_group`](Self::try_acknowledge_group).
+    ///
+    /// Time: O(1).
+    pub fn acknowledge_group(&self, handle: &OverflowHandle, group: u32) -> bool {
+        self.try_acknowledge_group(handle, group).is_ok()
+    }
+
+    /// Acknowledge an entry on behalf of consumer group `group`, reporting
+    /// why it was rejected. Repeated acks by one group count once.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// `UnknownGroup` if no group has id `group`, otherwise as for
+    /// `try_acknowledge`.
+    ///
+    /// Time: O(1) — atomic `fetch_or` on the entry record.
+    pub fn try_acknowledge_group(&self, handle: &OverflowHandle, group: u32) -> Result<()> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        let registered = self.ctrl.groups_mask().load(Ordering::Acquire);
+        if group as usize >= MAX_GROUPS || registered & (1 << group) == 0 {
+            return Err(BlobError::UnknownGroup(group));
+        }
+        if handle.is_inline() {
+            return Ok(());
+        }
+        let chunk = self.checked_chunk(handle)?;
+        Self::ack_entry(&chunk, handle, 1 << group)
+    }
+
+    /// Renew the lease on an entry: push its expiry out to at least
+    /// `extend_ms` from now, and keep its chunk from TTL reclamation until
+    /// then. Pass [`TTL_NEVER`](crate::types::TTL_NEVER) to keep the entry
+    /// until it is acknowledged. Never shortens an entry's lifetime.
+    ///
+    /// Renew with some margin: a sweep that has already found the chunk
+    /// expired may still free it.
+    ///
+    /// Errors: `LayoutFeatureRequired` unless the arena has entry records,
+    /// `HandleExpired` if the entry has already expired, `InvalidHandle`
+    /// for inline handles (their lifetime is fixed) and otherwise as for
+    /// `try_resolve`.
+    ///
+    /// Time: O(1).
+    pub fn touch(&self, handle: &OverflowHandle, extend_ms: u64) -> Result<()> {
+        if !self.layout.entry_records {
+            return Err(BlobError::LayoutFeatureRequired("entry_records"));
+        }
+        if handle.is_inline() {
+            return Err(BlobError::InvalidHandle);
+        }
+        let (chunk, entry) = self.checked_entry(handle)?;
+        let ttl_ms = self.ctrl.ttl_ms().load(Ordering::Acquire);
+        if Self::entry_expired(&chunk, &entry, ttl_ms) {
+            return Err(BlobError::HandleExpired);
+        }
+        let at = chunk
+            .record_at(entry.offset)
+            .ok_or(BlobError::InvalidHandle)?;
+        let extended = now_ms().saturating_add(extend_ms);
+        let renew = |current| match current {
+            // A concurrent delete wins
+            EXPIRES_DELETED => None,
+            // An entry on the shared TTL keeps that expiry as a floor
+            0 => Some(extended.max(handle.timestamp.saturating_add(ttl_ms))),
+            _ => Some(extended.max(current)),
+        };
+        let previous = chunk
+            .record_expires_at(at)
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, renew)
+            .map_err(|_| BlobError::HandleExpired)?;
+        let expires_at = renew(previous).unwrap_or(extended);
+        chunk
+            .max_expires_at()
+            .fetch_max(expires_at, Ordering::AcqRel);
+        Ok(())
+    }
+
+    // ── Compact handles ───────────────────────────────────────────────
+
+    /// Pack a handle issued by this namespace into a 16-byte `CompactHandle`.
+    ///
+    /// Errors: `NotCompactable` in arenas with authenticated handles, whose
+    /// tag has no room in the compact form, and if the page id, offset or
+    /// age within the chunk exceeds the compact limits; otherwise as for
+    /// `try_acknowledge`.
+    ///
+    /// Time: O(1).
+    pub fn compact(&self, handle: &OverflowHandle) -> Result<CompactHandle> {
+        if handle.is_inline() || self.handle_key.is_some() {
+            return Err(BlobError::NotCompactable);
+        }
+        let chunk = self.checked_chunk(handle)?;
+        let delta = handle
+            .timestamp
+            .checked_sub(chunk.first_write_ts().load(Ordering::Acquire))
+            .ok_or(BlobError::NotCompactable)?;
+        CompactHandle::pack(
+            handle.page_id,
+            handle.offset,
+            handle.size,
+            handle.generation,
+            delta,
+        )
+        .ok_or(BlobError::NotCompactable)
+    }
+
+    /// Rebuild the full `OverflowHandle` from a `CompactHandle`.
+    ///
+    /// The generation and timestamp are restored from the chunk, so
+    /// `expand(compact(h)) == h` for any live handle `h` issued by this
+    /// namespace instance. Fails with `InvalidHandle` once the chunk has
+    /// been recycled.
+    ///
+    /// The compact form carries no instance id: the handle is given this
+    /// instance's, so one kept from an earlier instance of a recreated
+    /// namespace is not rejected with `WrongInstance`. Only the low 16
+    /// bits of the generation guard against that. Arenas with
+    /// authenticated handles have no compact form and fail with
+    /// `NotCompactable` before any chunk is mapped.
+    ///
+    /// Time: O(1).
+    pub fn expand(&self, compact: &CompactHandle) -> Result<OverflowHandle> {
+        if self.handle_key.is_some() {
+            return Err(BlobError::NotCompactable);
+        }
+        let chunk = self
+            .get_or_map_chunk(compact.page_id())
+            .map_err(|_| BlobError::InvalidHandle)?;
+        let generation = chunk.generation();
+        let first_write = chunk.first_write_ts().load(Ordering::Acquire);
+        if generation as u16 != compact.generation_low() || first_write == 0 {
+            return Err(BlobError::InvalidHandle);
+        }
+
+        Ok(OverflowHandle {
+            page_id: compact.page_id(),
+            size: compact.size(),
+            offset: compact.offset(),
+            generation,
+            timestamp: first_write + compact.timestamp_delta_ms(),
+            tag: 0,
+            instance_id: self.instance_id,
+        })
+    }
+
+    /// [`resolve`](Self::resolve) for a `CompactHandle`.
+    ///
+    /// Time: O(d) where d = `size` (memcpy cost).
+    pub fn resolve_compact(&self, compact: &CompactHandle, ttl_ms: u64) -> Option<Vec<u8>> {
+        let handle = self.expand(compact).ok()?;
+        self.resolve(&handle, ttl_ms)
+    }
+
+    /// [`acknowledge`](Self::acknowledge) for a `CompactHandle`.
+    ///
+    /// Time: O(1).
+    pub fn acknowledge_compact(&self, compact: &CompactHandle) -> bool {
+        self.expand(compact)
+            .is_ok_and(|handle| self.acknowledge(&handle))
     }
 
     /// Sweep all chunks and recycle or free any that are fully
@@ -561,10 +2439,15 @@ impl SharedBackend {
     /// A chunk is eligible for recycling when **either**:
     /// - All entries are acknowledged (`ack_count >= entry_count`) AND
     ///   the decay timeout has elapsed, **or**
-    /// - The entire chunk has TTL-expired (`now - first_write_ts > ttl_ms`)
-    ///   regardless of ack state — this prevents stuck chunks when a consumer
+    /// - The entire chunk has TTL-expired (`now - last_write_ts > ttl_ms`,
+    ///   i.e. its newest entry is older than the TTL) regardless of ack state — this prevents stuck chunks when a consumer
     ///   crashes and never acknowledges.
     ///
+    /// The write head itself is never freed, but once it is fully acked and
+    /// has received no append for `decay_timeout_ms` it is sealed and the
+    /// head moves to a fresh (or pooled) chunk; the old head then decays on
+    /// a later sweep.
+    ///
     /// Eligible chunks are **unlinked from `/dev/shm`** and removed from
     /// the in-memory map, truly freeing tmpfs memory. New chunks are
     /// allocated on demand via `allocate_next_chunk()`.
@@ -579,6 +2462,10 @@ impl SharedBackend {
         let write_head = self.ctrl.write_head().load(Ordering::Acquire);
         let ts = now_ms();
 
+        // May move the write head on. `write_head` keeps the old id, so a
+        // chunk sealed here is never freed in the same sweep.
+        self.seal_idle_head(write_head, ts, decay_timeout_ms);
+
         // Phase 1: Identify which chunk IDs to free (under read lock).
         let to_free: Vec<u32> = {
             let chunks = self.chunks.read();
@@ -609,9 +2496,8 @@ impl SharedBackend {
                         }
                     }
 
-                    // Path B: TTL expired — all data in this chunk is stale
-                    let first_ts = chunk.first_write_ts().load(Ordering::Acquire);
-                    if first_ts > 0 && ts.saturating_sub(first_ts) > ttl_ms {
+                    // Path B: TTL expired — even the newest entry is stale
+                    if chunk.ttl_expired(ts, ttl_ms) {
                         return Some(id);
                     }
 
@@ -628,14 +2514,22 @@ impl SharedBackend {
         let mut chunks = self.chunks.write();
         let mut freed = 0;
         for id in &to_free {
-            if chunks.remove(id).is_some() {
+            if let Some(chunk) = chunks.remove(id) {
                 // SharedChunk::drop() handles munmap + close.
-                // Now unlink the shm file to free tmpfs memory.
+                // Now unlink the shm file to free tmpfs memory. Only the
+                // process whose unlink succeeds returns the chunk to the
+                // byte budget, so concurrent sweepers never double-count.
                 #[cfg(unix)]
                 {
                     if let Ok(name) = CString::new(format!("/{}_data_{}", self.namespace, id)) {
-                        unsafe {
-                            libc::shm_unlink(name.as_ptr());
+                        if unsafe { libc::shm_unlink(name.as_ptr()) } == 0 {
+                            let _ = self.ctrl.live_chunks().fetch_update(
+                                Ordering::AcqRel,
+                                Ordering::Acquire,
+                                |n| n.checked_sub(1),
+                            );
+                            // Likewise only that process reports what it drops
+                            self.collect_dead_letters(*id, &chunk, ts);
                         }
                     }
                 }
@@ -646,6 +2540,281 @@ impl SharedBackend {
         freed
     }
 
+    /// Record the entries of a chunk being freed that were never fully
+    /// acknowledged or released. Without entry records they cannot be told
+    /// apart, so the whole chunk is recorded as one letter instead.
+    ///
+    /// Time: O(e) where e = entries in the chunk; O(1) if all were acked
+    /// or the arena has no entry records.
+    fn collect_dead_letters(&self, id: u32, chunk: &SharedChunk, ts: u64) {
+        let entries = chunk.entry_count().load(Ordering::Acquire);
+        let acked = chunk.ack_count().load(Ordering::Acquire);
+        if acked >= entries {
+            return;
+        }
+        let generation = chunk.generation();
+        let first_write = chunk.first_write_ts().load(Ordering::Acquire);
+        let mut letters = self.dead_letters.lock();
+        if !self.layout.entry_records {
+            if letters.len() < DEAD_LETTER_CAPACITY {
+                letters.push(DeadLetter {
+                    handle: DeadLetterHandle::SharedChunk {
+                        page_id: id,
+                        generation,
+                        unacked: entries - acked,
+                    },
+                    age_ms: ts.saturating_sub(first_write),
+                    size: chunk.used().min(chunk.data_capacity() as u64),
+                });
+            }
+            return;
+        }
+        chunk.for_each_record(|at, offset, size| {
+            if chunk.record_refs(at).load(Ordering::Acquire) == 0
+                || letters.len() >= DEAD_LETTER_CAPACITY
+            {
+                return;
+            }
+            let written_at = chunk.record_written_at(at).load(Ordering::Acquire);
+            let mut handle = OverflowHandle::new(id, offset, size, generation);
+            handle.timestamp = written_at;
+            handle.tag = self.handle_tag(&handle);
+            handle.instance_id = self.instance_id;
+            letters.push(DeadLetter {
+                handle: DeadLetterHandle::Shared(handle),
+                age_ms: ts.saturating_sub(written_at),
+                size: size as u64,
+            });
+        });
+    }
+
+    /// Take the entries this process's sweeps dropped unacknowledged, in
+    /// the order they were found, or the chunks holding them in arenas
+    /// without entry records. At most [`DEAD_LETTER_CAPACITY`] are kept
+    /// between calls.
+    ///
+    /// Time: O(1) — swaps the list out.
+    pub fn take_dead_letters(&self) -> Vec<DeadLetter> {
+        std::mem::take(&mut *self.dead_letters.lock())
+    }
+
+    /// Rotate the write head away from a chunk that is fully acked and has
+    /// seen no new entry for `decay_timeout_ms`, so it can decay like any
+    /// other chunk instead of pinning its memory until the next append.
+    ///
+    /// The chunk is sealed by CASing `used` up to its capacity, starting
+    /// from the value read before the ack check: if any append claims space
+    /// in between, the CAS fails and the head is left alone. Appends that
+    /// still hold the old head id find it full and follow the new head.
+    /// The seal restarts the chunk's decay clock, so an append that claimed
+    /// space just before the seal has published its entry long before the
+    /// chunk can be freed.
+    ///
+    /// Time: O(c) when it rotates (see `allocate_next_chunk`), O(log c) otherwise.
+    fn seal_idle_head(&self, head: u32, ts: u64, decay_timeout_ms: u64) {
+        let chunk = match self.chunks.read().get(&head) {
+            Some(chunk) => Arc::clone(chunk),
+            None => return,
+        };
+        // Read before the counters: the seal CAS below expects this value.
+        let used = chunk.used();
+        let entries = chunk.entry_count().load(Ordering::Acquire);
+        let mut idle = self.idle_head.lock();
+        if entries == 0 || chunk.ack_count().load(Ordering::Acquire) < entries {
+            *idle = None;
+            return;
+        }
+        match *idle {
+            Some((id, seen, since)) if id == head && seen == entries => {
+                if ts.saturating_sub(since) < decay_timeout_ms {
+                    return;
+                }
+            }
+            _ => {
+                *idle = Some((head, entries, ts));
+                return;
+            }
+        }
+
+        // Allocate before sealing: a sealed head with nowhere to go would
+        // turn every append into OutOfMemory until the next sweep.
+        let Ok(next_id) = self.allocate_next_chunk(head) else {
+            return;
+        };
+        if chunk.cas_used(used, chunk.data_capacity() as u64).is_err() {
+            return;
+        }
+        chunk.empty_since().store(ts, Ordering::Release);
+        let _ = self.ctrl.write_head().compare_exchange(
+            head,
+            next_id,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+        *idle = None;
+    }
+
+    // ── Shared policy ─────────────────────────────────────────────────
+
+    /// Current shared policy, as stored in the control file.
+    ///
+    /// Time: O(1) — a handful of atomic loads.
+    pub fn config(&self) -> SharedConfig {
+        self.ctrl.load_config()
+    }
+
+    /// Random id of this namespace instance, carried in every handle it
+    /// issues. 0 for arenas created before instance ids existed.
+    pub fn instance_id(&self) -> u64 {
+        self.instance_id
+    }
+
+    /// Replace the shared policy for every attached process.
+    ///
+    /// Limits are not retroactive: lowering `max_chunks` or `byte_budget`
+    /// below the current usage only blocks further chunk allocation.
+    /// `layout` is fixed at creation; a different one is rejected.
+    ///
+    /// Time: O(1) — a handful of atomic stores.
+    pub fn set_config(&self, config: &SharedConfig) -> Result<()> {
+        config.validate(self.chunk_size as u64)?;
+        if config.layout != self.ctrl.load_config().layout {
+            return Err(BlobError::InvalidConfig("layout is fixed at creation"));
+        }
+        self.ctrl.store_config(config);
+        Ok(())
+    }
+
+    // ── Handle checks ─────────────────────────────────────────────────
+
+    /// SipHash-2-4 tag over `(page_id, offset, size, generation)`, or 0
+    /// when the arena does not authenticate handles.
+    fn handle_tag(&self, handle: &OverflowHandle) -> u64 {
+        let Some([k0, k1]) = self.handle_key else {
+            return 0;
+        };
+        let mut hasher = SipHasher24::new_with_keys(k0, k1);
+        hasher.write_u32(handle.page_id);
+        hasher.write_u64(handle.offset);
+        hasher.write_u32(handle.size);
+        hasher.write_u64(handle.generation);
+        hasher.finish()
+    }
+
+    /// True if the handle's tag verifies (always true without a key).
+    fn verify_tag(&self, handle: &OverflowHandle) -> bool {
+        self.handle_key.is_none() || handle.tag == self.handle_tag(handle)
+    }
+
+    /// Run every handle check that does not depend on time and return the
+    /// chunk the handle points into.
+    ///
+    /// Instance and tag are checked before any chunk is mapped, so foreign
+    /// or forged handles cannot make this process open arbitrary chunk ids.
+    fn checked_chunk(&self, handle: &OverflowHandle) -> Result<Arc<SharedChunk>> {
+        match self.checked_entry(handle)? {
+            (chunk, entry) if entry == *handle => Ok(chunk),
+            _ => Err(BlobError::InvalidHandle),
+        }
+    }
+
+    /// Like [`checked_chunk`](Self::checked_chunk), but also accept a
+    /// [slice](OverflowHandle::slice) of an entry. Returns the entry's own
+    /// handle along with the chunk: `*handle` unless it is a slice.
+    ///
+    /// With a key, every handle must verify before its chunk is mapped,
+    /// slices included (see [`slice`](Self::slice)); a verified handle that
+    /// matches no record is a slice. Without one, slices carry
+    /// [`SLICE_TAG`]. Slices are found by walking the chunk's records, so
+    /// they need entry records, and an entry is only found once the records
+    /// before it are complete.
+    fn checked_entry(&self, handle: &OverflowHandle) -> Result<(Arc<SharedChunk>, OverflowHandle)> {
+        if handle.instance_id != 0
+            && self.instance_id != 0
+            && handle.instance_id != self.instance_id
+        {
+            return Err(BlobError::WrongInstance {
+                expected: self.instance_id,
+                found: handle.instance_id,
+            });
+        }
+        if !self.verify_tag(handle) {
+            return Err(BlobError::InvalidHandle);
+        }
+        let marked = self.handle_key.is_none() && handle.tag == SLICE_TAG;
+        if marked
+            && (!self.layout.entry_records
+                || handle.page_id > self.ctrl.write_head().load(Ordering::Acquire))
+        {
+            return Err(BlobError::InvalidHandle);
+        }
+        let chunk = self
+            .get_or_map_chunk(handle.page_id)
+            .map_err(|_| BlobError::InvalidHandle)?;
+        if chunk.generation() != handle.generation || !Self::within_used(&chunk, handle) {
+            return Err(BlobError::InvalidHandle);
+        }
+        if !marked {
+            if Self::matches_record(&chunk, handle) {
+                return Ok((chunk, *handle));
+            }
+            if self.handle_key.is_none() {
+                return Err(BlobError::InvalidHandle);
+            }
+        }
+
+        let (offset, size) = chunk
+            .entry_covering(handle.offset, handle.size)
+            .ok_or(BlobError::InvalidHandle)?;
+        let mut entry = OverflowHandle {
+            offset,
+            size,
+            tag: 0,
+            ..*handle
+        };
+        entry.tag = self.handle_tag(&entry);
+        Ok((chunk, entry))
+    }
+
+    /// In arenas with entry records, true if a record for an entry of the
+    /// handle's size sits right before the handle's offset. Always true
+    /// without records.
+    fn matches_record(chunk: &SharedChunk, handle: &OverflowHandle) -> bool {
+        !chunk.layout.entry_records
+            || chunk
+                .record_at(handle.offset)
+                .is_some_and(|at| chunk.record_size(at).load(Ordering::Acquire) == handle.size)
+    }
+
+    /// True if the entry was deleted (arenas with entry records only).
+    fn is_deleted(chunk: &SharedChunk, handle: &OverflowHandle) -> bool {
+        chunk.record_at(handle.offset).is_some_and(|at| {
+            chunk.record_expires_at(at).load(Ordering::Acquire) == EXPIRES_DELETED
+        })
+    }
+
+    /// True if the entry is past its own expiry or, when it has none,
+    /// older than `ttl_ms`.
+    fn entry_expired(chunk: &SharedChunk, handle: &OverflowHandle, ttl_ms: u64) -> bool {
+        let own_expiry = chunk
+            .record_at(handle.offset)
+            .map_or(0, |at| chunk.record_expires_at(at).load(Ordering::Acquire));
+        if own_expiry != 0 {
+            now_ms() > own_expiry
+        } else {
+            handle.is_expired(ttl_ms)
+        }
+    }
+
+    /// True if the handle's range lies below the chunk's `used` watermark,
+    /// i.e. inside space some append has claimed.
+    fn within_used(chunk: &SharedChunk, handle: &OverflowHandle) -> bool {
+        handle
+            .offset
+            .checked_add(handle.size as u64)
+            .is_some_and(|end| end <= chunk.used())
+    }
+
     // ── Introspection ─────────────────────────────────────────────────
 
     /// Number of currently mapped chunks.
@@ -657,7 +2826,12 @@ impl SharedBackend {
 
     /// Usable bytes per chunk (total - header).
     pub fn data_capacity(&self) -> usize {
-        self.chunk_size - CHUNK_HEADER_SIZE
+        self.chunk_size - self.layout.header_size
+    }
+
+    /// Layout version of the attached arena (1 or 2).
+    pub fn layout_version(&self) -> u32 {
+        self.ctrl.version()
     }
 
     /// Print debug info about all mapped chunks.
@@ -674,8 +2848,8 @@ impl SharedBackend {
         for (&id, chunk) in chunks.iter() {
             let entries = chunk.entry_count().load(Ordering::Acquire);
             let acked = chunk.ack_count().load(Ordering::Acquire);
-            let used = chunk.used().load(Ordering::Acquire);
-            let gen = chunk.generation().load(Ordering::Acquire);
+            let used = chunk.used();
+            let gen = chunk.generation();
             let empty_ts = chunk.empty_since().load(Ordering::Acquire);
             eprintln!(
                 "    chunk[{}]: gen={} entries={} acked={} used={}/{} empty_since={} {}",
@@ -698,7 +2872,7 @@ impl SharedBackend {
     /// Strategy:
     /// 1. Try to find a recycled chunk (used == 0, entry_count == 0).
     /// 2. If none, atomically reserve a new chunk ID via CAS on `chunk_count`.
-    /// 3. If `max_chunks` is set and reached, return `OutOfMemory`.
+    /// 3. If `max_chunks` or `byte_budget` is set and reached, return `OutOfMemory`.
     ///
     /// Time: O(c) for the recycled-chunk scan, then O(1) amortised for
     /// the CAS loop on `chunk_count`. Worst case O(c) + one `shm_open` syscall.
@@ -708,22 +2882,25 @@ impl SharedBackend {
             return Ok(recycled_id);
         }
 
+        // Charge the byte budget first so a rejected allocation never
+        // burns a chunk ID.
+        self.reserve_budget()?;
+
         // Atomically reserve a new chunk ID via CAS on chunk_count.
         // This prevents two threads from racing to allocate the same ID.
-        loop {
+        let result = loop {
             let current_count = self.ctrl.chunk_count().load(Ordering::Acquire);
             let new_id = current_count;
 
             // Backpressure: enforce max_chunks limit
-            if let Some(max) = self.max_chunks {
-                if new_id >= max {
-                    return Err(BlobError::OutOfMemory);
-                }
+            let max = self.ctrl.max_chunks().load(Ordering::Acquire);
+            if max != 0 && new_id >= max {
+                break Err(BlobError::OutOfMemory);
             }
 
             // Wraparound protection
             if new_id == u32::MAX {
-                return Err(BlobError::OutOfMemory);
+                break Err(BlobError::OutOfMemory);
             }
 
             // Try to atomically claim this ID
@@ -739,13 +2916,38 @@ impl SharedBackend {
                 .is_ok()
             {
                 // We own this ID — now allocate the shm file
-                self.allocate_chunk(new_id)?;
-                return Ok(new_id);
+                break self.allocate_chunk(new_id).map(|_| new_id);
             }
 
             // Another thread won the race — retry
             std::hint::spin_loop();
+        };
+
+        if result.is_err() {
+            self.ctrl.live_chunks().fetch_sub(1, Ordering::AcqRel);
         }
+        result
+    }
+
+    /// Count one more live chunk against `byte_budget`.
+    ///
+    /// Returns `OutOfMemory` if another chunk would exceed the budget.
+    ///
+    /// Time: O(1) amortised — CAS loop on `live_chunks`.
+    fn reserve_budget(&self) -> Result<()> {
+        let chunk_size = self.chunk_size as u64;
+        self.ctrl
+            .live_chunks()
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |live| {
+                let budget = self.ctrl.byte_budget().load(Ordering::Acquire);
+                if budget != 0 && (live as u64 + 1) * chunk_size > budget {
+                    None
+                } else {
+                    Some(live + 1)
+                }
+            })
+            .map(|_| ())
+            .map_err(|_| BlobError::OutOfMemory)
     }
 
     /// Get a chunk reference, lazily mapping it if needed.
@@ -769,6 +2971,7 @@ impl SharedBackend {
             &self.namespace,
             id,
             self.chunk_size,
+            self.layout,
             false,
         )?);
         let mut chunks = self.chunks.write();
@@ -786,7 +2989,7 @@ impl SharedBackend {
             if id == skip_id {
                 continue;
             }
-            let used = chunk.used().load(Ordering::Acquire);
+            let used = chunk.used();
             let entries = chunk.entry_count().load(Ordering::Acquire);
             if used == 0 && entries == 0 {
                 return Some(id);
@@ -802,11 +3005,12 @@ impl SharedBackend {
     ///
     /// Time: O(1) + one `shm_open`/`ftruncate`/`mmap` syscall sequence.
     fn allocate_chunk(&self, id: u32) -> Result<()> {
-        let gen = self.ctrl.generation().fetch_add(1, Ordering::AcqRel) + 1;
+        let gen = self.ctrl.next_generation();
         let chunk = Arc::new(Self::open_chunk(
             &self.namespace,
             id,
             self.chunk_size,
+            self.layout,
             true,
         )?);
         unsafe { chunk.init(gen) };
@@ -822,10 +3026,10 @@ impl SharedBackend {
     // ── Platform-specific shm helpers ─────────────────────────────────
 
     #[cfg(unix)]
-    fn open_ctrl(namespace: &str, create: bool) -> Result<ControlFile> {
+    fn open_ctrl(namespace: &str, create: bool, size: usize) -> Result<ControlFile> {
         Self::shm_open_and_mmap(
             &format!("{}_ctrl", namespace),
-            CTRL_SIZE,
+            size,
             create,
             |ptr, fd, map_ptr, map_len| ControlFile {
                 ptr,
@@ -837,7 +3041,13 @@ impl SharedBackend {
     }
 
     #[cfg(unix)]
-    fn open_chunk(namespace: &str, id: u32, size: usize, create: bool) -> Result<SharedChunk> {
+    fn open_chunk(
+        namespace: &str,
+        id: u32,
+        size: usize,
+        layout: ChunkLayout,
+        create: bool,
+    ) -> Result<SharedChunk> {
         Self::shm_open_and_mmap(
             &format!("{}_data_{}", namespace, id),
             size,
@@ -845,6 +3055,7 @@ impl SharedBackend {
             |ptr, fd, map_ptr, map_len| SharedChunk {
                 ptr,
                 total_size: size,
+                layout,
                 fd,
                 _map_ptr: map_ptr,
                 _map_len: map_len,
@@ -911,12 +3122,252 @@ impl std::fmt::Debug for SharedBackend {
         f.debug_struct("SharedBackend")
             .field("namespace", &self.namespace)
             .field("chunk_size", &self.chunk_size)
-            .field("max_chunks", &self.max_chunks)
+            .field("config", &self.ctrl.load_config())
             .field("mapped_chunks", &self.chunks.read().len())
             .finish()
     }
 }
 
+/// Room reserved by [`SharedBackend::reserve`], written in place through
+/// `DerefMut<Target = [u8]>`. It starts zeroed.
+///
+/// [`commit`](Self::commit) publishes the entry. Dropping the slot
+/// uncommitted deletes it instead, so it never becomes readable and counts
+/// as acknowledged.
+pub struct SharedWriteSlot<'a> {
+    backend: &'a SharedBackend,
+    chunk: Arc<SharedChunk>,
+    handle: OverflowHandle,
+    committed: bool,
+}
+
+impl SharedWriteSlot<'_> {
+    /// Publish the entry and return its handle. The shared TTL runs from
+    /// the commit, not the reservation.
+    ///
+    /// Errors: `InvalidHandle` if the entry was deleted meanwhile.
+    pub fn commit(mut self) -> Result<OverflowHandle> {
+        self.committed = true;
+        self.backend.commit_reserved(&self.chunk, &self.handle)
+    }
+}
+
+impl std::ops::Deref for SharedWriteSlot<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the slot owns the reserved range until it is committed or
+        // dropped, and holds the chunk's mapping.
+        unsafe {
+            std::slice::from_raw_parts(
+                self.chunk.data_ptr().add(self.handle.offset as usize),
+                self.handle.size as usize,
+            )
+        }
+    }
+}
+
+impl std::ops::DerefMut for SharedWriteSlot<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as for `deref`; nobody else writes the range.
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.chunk.data_ptr().add(self.handle.offset as usize),
+                self.handle.size as usize,
+            )
+        }
+    }
+}
+
+impl Drop for SharedWriteSlot<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            SharedBackend::abandon_reserved(&self.chunk, &self.handle);
+        }
+    }
+}
+
+impl std::fmt::Debug for SharedWriteSlot<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedWriteSlot")
+            .field("page_id", &self.handle.page_id)
+            .field("offset", &self.handle.offset)
+            .field("len", &self.handle.size)
+            .finish()
+    }
+}
+
+/// Writer for an entry of unknown length, from [`SharedBackend::writer`].
+///
+/// Bytes go straight into a pending entry, which nobody can read until
+/// [`finish`](Self::finish). Dropping the writer unfinished deletes it.
+/// Capped at one chunk; see [`SharedBackend::writer`].
+pub struct SharedBlobWriter<'a> {
+    backend: &'a SharedBackend,
+    /// Entry being filled, absent until the first write
+    slot: Option<SharedWriteSlot<'a>>,
+    /// Bytes written so far
+    len: usize,
+}
+
+impl SharedBlobWriter<'_> {
+    /// Bytes written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if nothing has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Publish everything written and return its handle. The shared TTL
+    /// runs from here.
+    ///
+    /// Errors: `DataTooLarge` if nothing was written, `InvalidHandle` if
+    /// the entry was deleted meanwhile, otherwise as for
+    /// [`append`](SharedBackend::append).
+    pub fn finish(mut self) -> Result<OverflowHandle> {
+        let Some(mut slot) = self.slot.take() else {
+            return Err(BlobError::DataTooLarge {
+                size: 0,
+                max: self.backend.max_entry_len(self.backend.default_align()),
+            });
+        };
+        slot.handle = SharedBackend::shrink_reserved(&slot.chunk, &slot.handle, self.len)?;
+        slot.commit()
+    }
+}
+
+impl std::io::Write for SharedBlobWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.slot.is_none() {
+            let max = self.backend.max_entry_len(self.backend.default_align());
+            let slot = self.backend.reserve(max).map_err(std::io::Error::other)?;
+            self.slot = Some(slot);
+        }
+        let slot = self.slot.as_mut().expect("reserved above");
+        // All or nothing, so the cap surfaces at the write that crosses it
+        // rather than as a short write
+        let end = self.len + buf.len();
+        if end > slot.len() {
+            return Err(std::io::Error::other(BlobError::DataTooLarge {
+                size: end,
+                max: slot.len(),
+            }));
+        }
+        slot[self.len..end].copy_from_slice(buf);
+        self.len = end;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for SharedBlobWriter<'_> {
+    fn drop(&mut self) {
+        // Hand the unwritten rest of the chunk back before the slot
+        // deletes the entry
+        if let Some(slot) = &mut self.slot {
+            if let Ok(handle) =
+                SharedBackend::shrink_reserved(&slot.chunk, &slot.handle, self.len.max(1))
+            {
+                slot.handle = handle;
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for SharedBlobWriter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedBlobWriter")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// Reader over a shared entry, from [`SharedBackend::open_reader`].
+#[derive(Debug, Clone)]
+pub struct SharedBlobReader<'a> {
+    backend: &'a SharedBackend,
+    handle: OverflowHandle,
+    ttl_ms: u64,
+    pos: u64,
+}
+
+impl SharedBlobReader<'_> {
+    /// Size of the entry in bytes.
+    pub fn len(&self) -> u64 {
+        self.handle.size as u64
+    }
+
+    /// True if the entry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.handle.size == 0
+    }
+
+    /// Handle of the entry being read.
+    pub fn handle(&self) -> &OverflowHandle {
+        &self.handle
+    }
+}
+
+impl std::io::Read for SharedBlobReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self
+            .backend
+            .read_entry(&self.handle, self.ttl_ms, self.pos, buf)
+            .map_err(std::io::Error::other)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl std::io::Seek for SharedBlobReader<'_> {
+    fn seek(&mut self, to: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.pos = seek_position(self.handle.size as u64, self.pos, to)?;
+        Ok(self.pos)
+    }
+
+    fn stream_position(&mut self) -> std::io::Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+/// A `T` read from a shared arena, from [`SharedBackend::view`].
+/// Dereferences to the value, which is a copy.
+pub struct SharedValueRef<T: Pod> {
+    value: T,
+}
+
+impl<T: Pod> SharedValueRef<T> {
+    /// The value itself.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Pod> std::ops::Deref for SharedValueRef<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Pod + std::fmt::Debug> std::fmt::Debug for SharedValueRef<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedValueRef")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
 impl Drop for SharedBackend {
     fn drop(&mut self) {
         // Unmap all chunks (SharedChunk Drop handles munmap + close)
@@ -4,12 +4,20 @@
 //! - Basic CRUD: create, append, resolve, acknowledge
 //! - Chunk overflow and multi-chunk allocation
 //! - Cross-process attach and resolve
-//! - Backpressure via `max_chunks`
+//! - Backpressure via `max_chunks` and `byte_budget`
+//! - Shared policy stored in the control file
+//! - Authenticated handles, instance ids and the `used` watermark check
+//! - Compact 16-byte handles and inline small-payload handles
+//! - Entry records, per-entry TTL, lease renewal, claims, consumer groups,
+//!   reference counts, deletion, dead letters, streaming writers and
+//!   readers, range reads, slices, aligned appends, typed values
+//! - Layout version negotiation (v1 / v2 / unknown) and split-counter headers
 //! - Input validation (chunk size, namespace)
 //! - Crash recovery via `cleanup_namespace()`
 //! - Concurrent stress: parallel appends, parallel resolve, full lifecycle
 
 use super::*;
+use crate::types::{Handle, Pod, MAX_ALIGN, TTL_NEVER};
 use std::sync::Arc;
 
 /// Generate a unique namespace per test to avoid cross-test interference.
@@ -46,7 +54,7 @@ fn test_attach_and_resolve() {
     let data = b"cross-process payload";
     let handle = backend_a.append(data).unwrap();
 
-    let backend_b = SharedBackend::attach(&ns, None).unwrap();
+    let backend_b = SharedBackend::attach(&ns).unwrap();
     let resolved = backend_b.resolve(&handle, 30_000).unwrap();
     assert_eq!(resolved, data);
 
@@ -100,6 +108,1416 @@ fn test_max_chunks_backpressure() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_byte_budget_backpressure() {
+    let ns = test_namespace();
+    let chunk_size = CHUNK_HEADER_SIZE + 64;
+    let config = SharedConfig {
+        byte_budget: Some(2 * chunk_size as u64),
+        prefetch_threshold: 1.0,
+        ..SharedConfig::default()
+    };
+    let backend = SharedBackend::create_with_config(&ns, chunk_size, &config).unwrap();
+
+    let h1 = backend.append(&[0xAAu8; 60]).unwrap();
+    let _h2 = backend.append(&[0xBBu8; 60]).unwrap();
+    // Budget covers exactly two chunks
+    assert!(matches!(
+        backend.append(&[0xCCu8; 60]),
+        Err(BlobError::OutOfMemory)
+    ));
+
+    // Freeing chunk 0 returns its bytes to the budget
+    backend.acknowledge(&h1);
+    assert_eq!(backend.cleanup_chunks(30_000, 0), 1);
+    assert!(backend.append(&[0xCCu8; 60]).is_ok());
+}
+
+// ── Shared policy ────────────────────────────────────────────────────────
+
+#[test]
+fn test_legacy_attach_counts_present_chunks() {
+    let ns = test_namespace();
+    let chunk_size = CHUNK_HEADER_SIZE + 128;
+    let creator = SharedBackend::create(&ns, chunk_size, None).unwrap();
+    let first = creator.append(&[1u8; 100]).unwrap();
+    creator.append(&[2u8; 100]).unwrap();
+    creator.append(&[3u8; 100]).unwrap();
+    assert!(creator.acknowledge(&first));
+    assert_eq!(creator.cleanup_chunks(30_000, 0), 1);
+
+    // As left by a version that kept no policy or live count
+    creator
+        .ctrl
+        .flags()
+        .fetch_and(!CTRL_FLAG_SHARED_CONFIG, Ordering::AcqRel);
+    creator.ctrl.live_chunks().store(0, Ordering::Release);
+
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert_eq!(attacher.ctrl.chunk_count().load(Ordering::Acquire), 3);
+    assert_eq!(attacher.ctrl.live_chunks().load(Ordering::Acquire), 2);
+    assert_eq!(attacher.config(), SharedConfig::default());
+}
+
+#[test]
+fn test_attach_adopts_creator_config() {
+    let ns = test_namespace();
+    let config = SharedConfig {
+        ttl_ms: 1234,
+        decay_timeout_ms: 56,
+        max_chunks: Some(7),
+        prefetch_threshold: 0.5,
+        byte_budget: Some(1 << 20),
+        align: 64,
+        layout: ArenaLayout::default(),
+    };
+    let _creator = SharedBackend::create_with_config(&ns, 4096, &config).unwrap();
+
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert_eq!(attacher.config(), config);
+}
+
+#[test]
+fn test_set_config_visible_to_attachers() {
+    let ns = test_namespace();
+    let creator = SharedBackend::create(&ns, 4096, None).unwrap();
+    let attacher = SharedBackend::attach(&ns).unwrap();
+
+    let updated = SharedConfig {
+        ttl_ms: 10,
+        max_chunks: Some(3),
+        ..creator.config()
+    };
+    attacher.set_config(&updated).unwrap();
+    assert_eq!(creator.config(), updated);
+
+    let bad = SharedConfig {
+        prefetch_threshold: 1.5,
+        ..updated.clone()
+    };
+    assert!(matches!(
+        creator.set_config(&bad),
+        Err(BlobError::InvalidConfig(_))
+    ));
+
+    // A budget that cannot hold one chunk would stop every allocation
+    let tiny = SharedConfig {
+        byte_budget: Some(4095),
+        ..updated.clone()
+    };
+    assert!(matches!(
+        attacher.set_config(&tiny),
+        Err(BlobError::InvalidConfig(_))
+    ));
+
+    // Layout cannot change after creation
+    let relayout = SharedConfig {
+        layout: ArenaLayout {
+            split_counters: true,
+            ..ArenaLayout::default()
+        },
+        ..updated.clone()
+    };
+    assert!(matches!(
+        creator.set_config(&relayout),
+        Err(BlobError::InvalidConfig(_))
+    ));
+    assert_eq!(attacher.config(), updated);
+}
+
+// ── Layout versions ──────────────────────────────────────────────────────
+
+#[test]
+fn test_v1_arena_attach_read_write() {
+    let ns = test_namespace();
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            version: 1,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let creator = SharedBackend::create_with_config(&ns, 4096, &config).unwrap();
+    let h1 = creator.append(b"written by creator").unwrap();
+
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert_eq!(attacher.layout_version(), 1);
+    assert_eq!(attacher.data_capacity(), 4096 - CHUNK_HEADER_SIZE);
+    assert_eq!(
+        attacher.resolve(&h1, 30_000).unwrap(),
+        b"written by creator"
+    );
+
+    // Writes from the attacher land after the creator's entry
+    let h2 = attacher.append(b"written by attacher").unwrap();
+    assert_eq!(h2.offset, h1.size as u64);
+    assert_eq!(
+        creator.resolve(&h2, 30_000).unwrap(),
+        b"written by attacher"
+    );
+
+    // Handles from a v1 arena always have a v1 encoding, which drops
+    // the instance id but still resolves
+    let v1 = OverflowHandle::from_bytes(&h2.to_v1_bytes().unwrap()).unwrap();
+    assert_eq!(
+        v1,
+        OverflowHandle {
+            instance_id: 0,
+            ..h2
+        }
+    );
+    assert_eq!(
+        creator.resolve(&v1, 30_000).unwrap(),
+        b"written by attacher"
+    );
+}
+
+#[test]
+fn test_v2_is_default_and_roundtrips() {
+    let ns = test_namespace();
+    let creator = SharedBackend::create(&ns, 4096, None).unwrap();
+    assert_eq!(creator.layout_version(), 2);
+
+    let h = creator.append(b"v2 payload").unwrap();
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert_eq!(attacher.layout_version(), 2);
+    assert_eq!(attacher.resolve(&h, 30_000).unwrap(), b"v2 payload");
+}
+
+#[test]
+fn test_attach_rejects_unknown_layout() {
+    let ns = test_namespace();
+    let creator = SharedBackend::create(&ns, 4096, None).unwrap();
+    let p = creator.ctrl.ptr.as_ptr();
+
+    // A future incompatible feature bit
+    unsafe { (p.add(68) as *mut u32).write(1 << 31) };
+    assert!(matches!(
+        SharedBackend::attach(&ns),
+        Err(BlobError::UnsupportedLayout { version: 2, .. })
+    ));
+
+    // A future version
+    unsafe {
+        (p.add(68) as *mut u32).write(0);
+        (p.add(8) as *mut u32).write(3);
+    }
+    assert!(matches!(
+        SharedBackend::attach(&ns),
+        Err(BlobError::UnsupportedLayout { version: 3, .. })
+    ));
+    unsafe { (p.add(8) as *mut u32).write(2) };
+}
+
+#[test]
+fn test_v1_chunk_size_limit() {
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            version: 1,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    // Rejected before any shm file is created
+    assert!(matches!(
+        SharedBackend::create_with_config(&test_namespace(), u32::MAX as usize + 1, &config),
+        Err(BlobError::DataTooLarge { .. })
+    ));
+    assert!(
+        SharedBackend::validate_chunk_size(u32::MAX as usize + 1, &ArenaLayout::default()).is_ok()
+    );
+}
+
+#[test]
+fn test_split_counters_layout() {
+    let ns = test_namespace();
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            split_counters: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let creator = SharedBackend::create_with_config(&ns, 4096, &config).unwrap();
+    assert_eq!(creator.data_capacity(), 4096 - SPLIT_CHUNK_HEADER_SIZE);
+
+    let h = creator.append(b"split payload").unwrap();
+
+    // Attacher picks the layout up from the control file
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert!(attacher.config().layout.split_counters);
+    assert_eq!(attacher.resolve(&h, 30_000).unwrap(), b"split payload");
+    assert!(attacher.acknowledge(&h));
+
+    // Producer and consumer counters land on different cache lines
+    let chunks = creator.chunks.read();
+    let chunk = chunks.get(&0).unwrap();
+    assert_eq!(chunk.entry_count().load(Ordering::Acquire), 1);
+    assert_eq!(chunk.ack_count().load(Ordering::Acquire), 1);
+    let layout = creator.layout;
+    assert_ne!(layout.used / 64, layout.ack_count / 64);
+    assert_ne!(layout.entry_count / 64, layout.empty_since / 64);
+}
+
+#[test]
+fn test_split_counters_requires_v2() {
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            version: 1,
+            split_counters: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    assert!(matches!(
+        SharedBackend::create_with_config(&test_namespace(), 4096, &config),
+        Err(BlobError::InvalidConfig(_))
+    ));
+
+    // Split header leaves no data space at this size
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            split_counters: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    assert!(
+        SharedBackend::create_with_config(&test_namespace(), SPLIT_CHUNK_HEADER_SIZE, &config)
+            .is_err()
+    );
+}
+
+// ── Handle authentication ────────────────────────────────────────────────
+
+#[test]
+fn test_authenticated_handles_reject_forgery() {
+    let ns = test_namespace();
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            authenticated_handles: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let creator = SharedBackend::create_with_config(&ns, 4096, &config).unwrap();
+    let h = creator.append(b"signed").unwrap();
+    assert_ne!(h.tag, 0);
+
+    // Attacher learns the key from the control file
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert!(attacher.config().layout.authenticated_handles);
+    assert_eq!(attacher.resolve(&h, 30_000).unwrap(), b"signed");
+
+    // Any altered field, or a missing tag, fails verification
+    let shifted = OverflowHandle { offset: 1, ..h };
+    let grown = OverflowHandle { size: 64, ..h };
+    let untagged = OverflowHandle { tag: 0, ..h };
+    for forged in [shifted, grown, untagged] {
+        assert!(attacher.resolve(&forged, 30_000).is_none());
+        assert!(!attacher.acknowledge(&forged));
+    }
+    assert!(attacher.acknowledge(&h));
+}
+
+#[test]
+fn test_resolve_rejects_unwritten_range() {
+    let ns = test_namespace();
+    let backend = SharedBackend::create(&ns, 4096, None).unwrap();
+    let h = backend.append(b"written").unwrap();
+    assert_eq!(h.tag, 0);
+
+    // Inside the chunk's capacity but past anything appended
+    let beyond = OverflowHandle::new(0, 1024, 16, h.generation);
+    assert!(backend.resolve(&beyond, 30_000).is_none());
+    assert!(!backend.acknowledge(&beyond));
+
+    let straddling = OverflowHandle { size: 8, ..h };
+    assert!(backend.resolve(&straddling, 30_000).is_none());
+}
+
+#[test]
+fn test_recreated_namespace_rejects_old_handles() {
+    let ns = test_namespace();
+    let first = SharedBackend::create(&ns, 4096, None).unwrap();
+    let old = first.append(b"first life").unwrap();
+    assert_ne!(old.instance_id, 0);
+    assert_eq!(old.instance_id, first.instance_id());
+    drop(first);
+
+    // Same name, same chunk 0, same generation — only the instance differs
+    let second = SharedBackend::create(&ns, 4096, None).unwrap();
+    let new = second.append(b"second life").unwrap();
+    assert_eq!(
+        (new.page_id, new.offset, new.generation),
+        (old.page_id, old.offset, old.generation)
+    );
+
+    assert!(matches!(
+        second.try_resolve(&old, 30_000),
+        Err(BlobError::WrongInstance { expected, found })
+            if expected == second.instance_id() && found == old.instance_id
+    ));
+    assert!(matches!(
+        second.try_acknowledge(&old),
+        Err(BlobError::WrongInstance { .. })
+    ));
+    assert_eq!(second.try_resolve(&new, 30_000).unwrap(), b"second life");
+
+    // Legacy handles without an instance id are still accepted
+    let legacy = OverflowHandle {
+        instance_id: 0,
+        ..new
+    };
+    assert_eq!(second.resolve(&legacy, 30_000).unwrap(), b"second life");
+}
+
+// ── Compact handles ──────────────────────────────────────────────────────
+
+#[test]
+fn test_compact_handle_cross_process() {
+    let ns = test_namespace();
+    let creator = SharedBackend::create(&ns, 4096, None).unwrap();
+    let attacher = SharedBackend::attach(&ns).unwrap();
+
+    let h1 = creator.append(b"first").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    let h2 = creator.append(b"second").unwrap();
+    let c2 = creator.compact(&h2).unwrap();
+    assert_eq!(c2.as_bytes().len(), 16);
+
+    // Only the 16 bytes cross the process boundary
+    let received = CompactHandle::from_bytes(c2.as_bytes()).unwrap();
+    assert_eq!(attacher.expand(&received).unwrap(), h2);
+    assert_eq!(
+        attacher.resolve_compact(&received, 30_000).unwrap(),
+        b"second"
+    );
+    assert!(attacher.acknowledge_compact(&received));
+
+    let c1 = attacher.compact(&h1).unwrap();
+    assert_eq!(creator.expand(&c1).unwrap(), h1);
+    assert!(c1.timestamp_delta_ms() <= c2.timestamp_delta_ms());
+}
+
+#[test]
+fn test_compact_handles_refused_when_authenticated() {
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            authenticated_handles: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let backend = SharedBackend::create_with_config(&test_namespace(), 4096, &config).unwrap();
+    let h = backend.append(b"secret").unwrap();
+    assert!(matches!(
+        backend.compact(&h),
+        Err(BlobError::NotCompactable)
+    ));
+
+    // Anyone can build the 16 bytes; they must not pass for a real handle
+    let forged = CompactHandle::pack(h.page_id, h.offset, h.size, h.generation, 0).unwrap();
+    let forged = CompactHandle::from_bytes(forged.as_bytes()).unwrap();
+    let mapped = backend.chunk_count();
+    assert!(matches!(
+        backend.expand(&forged),
+        Err(BlobError::NotCompactable)
+    ));
+    assert_eq!(backend.resolve_compact(&forged, 30_000), None);
+    assert!(!backend.acknowledge_compact(&forged));
+    assert_eq!(backend.chunk_count(), mapped);
+    assert_eq!(backend.resolve(&h, 30_000).unwrap(), b"secret");
+}
+
+#[test]
+fn test_compact_handle_limits() {
+    let ns = test_namespace();
+    let backend = SharedBackend::create(&ns, 4096, None).unwrap();
+    let h = backend.append(b"aging").unwrap();
+
+    // More than ~4.6 hours after the chunk's first write
+    let old = OverflowHandle {
+        timestamp: h.timestamp + (1 << 24),
+        ..h
+    };
+    assert!(matches!(
+        backend.compact(&old),
+        Err(BlobError::NotCompactable)
+    ));
+
+    // Forged handles are refused before packing
+    let beyond = OverflowHandle { size: 4000, ..h };
+    assert!(matches!(
+        backend.compact(&beyond),
+        Err(BlobError::InvalidHandle)
+    ));
+}
+
+#[test]
+fn test_append_vectored() {
+    use std::io::IoSlice;
+
+    let header = b"hdr:";
+    let body = b"payload body";
+    for config in [SharedConfig::default(), records_config()] {
+        let backend = SharedBackend::create_with_config(&test_namespace(), 4096, &config).unwrap();
+        let gathered = backend
+            .append_vectored(&[IoSlice::new(header), IoSlice::new(body)])
+            .unwrap();
+        let joined = backend.append(&[&header[..], &body[..]].concat()).unwrap();
+
+        // Laid out exactly like the joined append
+        assert_eq!(gathered.size, joined.size);
+        assert_eq!(
+            joined.offset - gathered.offset,
+            (backend.layout.record_bytes() + 16) as u64
+        );
+        assert_eq!(
+            backend.resolve(&gathered, 30_000).unwrap(),
+            backend.resolve(&joined, 30_000).unwrap()
+        );
+        let chunks = backend.chunks.read();
+        assert_eq!(chunks[&0].entry_count().load(Ordering::Acquire), 2);
+    }
+}
+
+#[test]
+fn test_reserve_and_commit() {
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    assert!(matches!(
+        plain.reserve(8),
+        Err(BlobError::LayoutFeatureRequired("entry_records"))
+    ));
+
+    let ns = test_namespace();
+    let backend = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let mut slot = backend.reserve(6).unwrap();
+    assert!(slot.iter().all(|&b| b == 0));
+    slot.copy_from_slice(b"framed");
+
+    // Not readable before the commit, even through a forged handle
+    let next = backend.append(b"next").unwrap();
+    let pending = OverflowHandle {
+        offset: ENTRY_RECORD_SIZE as u64,
+        size: 6,
+        ..next
+    };
+    assert!(matches!(
+        backend.try_resolve(&pending, 30_000),
+        Err(BlobError::HandleExpired)
+    ));
+
+    let handle = slot.commit().unwrap();
+    assert_eq!(handle.offset, pending.offset);
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert_eq!(attacher.resolve(&handle, 30_000).unwrap(), b"framed");
+
+    // An abandoned slot is deleted, which counts as its ack
+    drop(backend.reserve(16).unwrap());
+    let chunks = backend.chunks.read();
+    assert_eq!(chunks[&0].entry_count().load(Ordering::Acquire), 3);
+    assert_eq!(chunks[&0].ack_count().load(Ordering::Acquire), 1);
+}
+
+#[test]
+fn test_blob_writer() {
+    use std::io::Write;
+
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    assert!(matches!(
+        plain.writer(),
+        Err(BlobError::LayoutFeatureRequired("entry_records"))
+    ));
+
+    let ns = test_namespace();
+    let backend = SharedBackend::create_with_config(&ns, 65536, &records_config()).unwrap();
+
+    // Written in place, in a chunk of its own
+    let data: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+    let mut writer = backend.writer().unwrap();
+    for piece in data.chunks(999) {
+        writer.write_all(piece).unwrap();
+    }
+    let handle = writer.finish().unwrap();
+    assert_eq!(handle.size, 10_000);
+    assert_eq!(handle.page_id, 0);
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert_eq!(attacher.resolve(&handle, 30_000).unwrap(), data);
+
+    // `finish` handed the rest of the chunk back, so the next append
+    // follows the entry and the records still chain
+    let next = backend.append(b"next").unwrap();
+    assert_eq!(next.page_id, 0);
+    {
+        let chunks = backend.chunks.read();
+        let mut sizes = Vec::new();
+        chunks[&0].for_each_record(|_, _, size| sizes.push(size));
+        assert_eq!(sizes, [10_000, 4]);
+    }
+
+    // Nothing past the largest entry fits
+    let max = backend.max_entry_len(backend.default_align());
+    let mut writer = backend.writer().unwrap();
+    writer.write_all(&vec![7u8; max - 1]).unwrap();
+    // A write crossing the cap fails whole, rather than writing part
+    let err = writer.write(b"xy").unwrap_err();
+    assert!(matches!(
+        err.into_inner().unwrap().downcast::<BlobError>().as_deref(),
+        Ok(&BlobError::DataTooLarge { size, max: cap }) if size == max + 1 && cap == max
+    ));
+    assert_eq!(writer.len(), max - 1);
+    writer.write_all(b"x").unwrap();
+    assert_eq!(writer.finish().unwrap().size as usize, max);
+
+    // An unfinished writer deletes its entry, and hands its chunk back
+    let mut abandoned = backend.writer().unwrap();
+    abandoned.write_all(b"partial").unwrap();
+    drop(abandoned);
+    let after = backend.append(b"after").unwrap();
+    let chunks = backend.chunks.read();
+    let chunk = &chunks[&after.page_id];
+    assert_eq!(chunk.entry_count().load(Ordering::Acquire), 2);
+    assert_eq!(chunk.ack_count().load(Ordering::Acquire), 1);
+    let mut sizes = Vec::new();
+    chunk.for_each_record(|_, _, size| sizes.push(size));
+    assert_eq!(sizes, [7, 5]);
+}
+
+#[test]
+fn test_blob_reader() {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let ns = test_namespace();
+    let backend = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let data: Vec<u8> = (0..100u8).collect();
+    let handle = backend.append(&data).unwrap();
+
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    let mut reader = attacher.open_reader(&handle, 30_000).unwrap();
+    let mut head = [0u8; 10];
+    reader.read_exact(&mut head).unwrap();
+    assert_eq!(head[..], data[..10]);
+    reader.seek(SeekFrom::End(-5)).unwrap();
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail).unwrap();
+    assert_eq!(tail, data[95..]);
+
+    // Inline handles read from the handle itself
+    let inline = backend.append_auto(b"tiny").unwrap();
+    let mut text = String::new();
+    backend
+        .open_reader(&inline, 30_000)
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    assert_eq!(text, "tiny");
+
+    // A delete fails the next read
+    reader.rewind().unwrap();
+    backend.delete(&handle).unwrap();
+    let err = reader.read(&mut head).unwrap_err();
+    assert!(matches!(
+        err.get_ref().and_then(|e| e.downcast_ref::<BlobError>()),
+        Some(BlobError::HandleExpired)
+    ));
+}
+
+#[test]
+fn test_read_at_and_get_into() {
+    let ns = test_namespace();
+    let backend = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let data: Vec<u8> = (0..100u8).collect();
+    let handle = backend.append(&data).unwrap();
+
+    let mut header = [0u8; 8];
+    assert_eq!(backend.read_at(&handle, 30_000, 0, &mut header).unwrap(), 8);
+    assert_eq!(header[..], data[..8]);
+    let mut tail = [0u8; 16];
+    assert_eq!(backend.read_at(&handle, 30_000, 90, &mut tail).unwrap(), 10);
+    assert_eq!(tail[..10], data[90..]);
+
+    let mut buf = vec![1u8; 500];
+    assert_eq!(backend.get_into(&handle, 30_000, &mut buf).unwrap(), 100);
+    assert_eq!(buf, data);
+    let inline = backend.append_auto(b"tiny").unwrap();
+    backend.get_into(&inline, 30_000, &mut buf).unwrap();
+    assert_eq!(buf, b"tiny");
+
+    // Rejected exactly when try_resolve is
+    backend.delete(&handle).unwrap();
+    assert!(matches!(
+        backend.try_resolve(&handle, 30_000),
+        Err(BlobError::HandleExpired)
+    ));
+    assert!(matches!(
+        backend.read_at(&handle, 30_000, 0, &mut header),
+        Err(BlobError::HandleExpired)
+    ));
+    assert!(matches!(
+        backend.get_into(&handle, 30_000, &mut buf),
+        Err(BlobError::HandleExpired)
+    ));
+    assert!(buf.is_empty());
+}
+
+// ── Inline handles ───────────────────────────────────────────────────────
+
+#[test]
+fn test_append_auto_inlines_small_payloads() {
+    let ns = test_namespace();
+    let creator = SharedBackend::create(&ns, 4096, None).unwrap();
+    let attacher = SharedBackend::attach(&ns).unwrap();
+
+    let small = creator.append_auto(b"tiny").unwrap();
+    assert!(small.is_inline());
+    let large = creator.append_auto(&[7u8; 100]).unwrap();
+    assert!(!large.is_inline());
+
+    // Only the large payload reached shared memory
+    {
+        let chunks = creator.chunks.read();
+        let chunk = chunks.get(&0).unwrap();
+        assert_eq!(chunk.entry_count().load(Ordering::Acquire), 1);
+        assert_eq!(chunk.used(), 100);
+    }
+
+    let received = OverflowHandle::from_bytes(small.as_bytes()).unwrap();
+    assert_eq!(attacher.resolve(&received, 30_000).unwrap(), b"tiny");
+    assert!(attacher.acknowledge(&received));
+    assert_eq!(attacher.resolve(&large, 30_000).unwrap(), vec![7u8; 100]);
+
+    // Inline acks never touch the chunk counters
+    let chunks = creator.chunks.read();
+    assert_eq!(
+        chunks.get(&0).unwrap().ack_count().load(Ordering::Acquire),
+        0
+    );
+}
+
+// ── Entry records ────────────────────────────────────────────────────────
+
+fn records_config() -> SharedConfig {
+    SharedConfig {
+        layout: ArenaLayout {
+            entry_records: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    }
+}
+
+#[test]
+fn test_entry_records_layout() {
+    let ns = test_namespace();
+    let creator = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+
+    // Payloads follow their record; entries start 8-byte aligned
+    let h1 = creator.append(b"odd").unwrap();
+    let h2 = creator.append(b"next").unwrap();
+    assert_eq!(h1.offset, ENTRY_RECORD_SIZE as u64);
+    assert_eq!(h2.offset, 2 * ENTRY_RECORD_SIZE as u64 + 8);
+
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert!(attacher.config().layout.entry_records);
+    assert_eq!(attacher.resolve(&h2, 30_000).unwrap(), b"next");
+
+    // A handle that does not line up with a record is rejected
+    let shifted = OverflowHandle {
+        offset: h2.offset - 8,
+        ..h2
+    };
+    assert!(matches!(
+        attacher.try_resolve(&shifted, 30_000),
+        Err(BlobError::InvalidHandle)
+    ));
+    let resized = OverflowHandle { size: 3, ..h2 };
+    assert!(!attacher.acknowledge(&resized));
+
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            version: 1,
+            entry_records: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    assert!(matches!(
+        SharedBackend::create_with_config(&test_namespace(), 4096, &config),
+        Err(BlobError::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn test_append_with_ttl() {
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    assert!(matches!(
+        plain.append_with_ttl(b"data", 1_000),
+        Err(BlobError::LayoutFeatureRequired("entry_records"))
+    ));
+
+    let ns = test_namespace();
+    let backend = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let shared_ttl = backend.append(b"shared ttl").unwrap();
+    let long = backend.append_with_ttl(b"long", 60_000).unwrap();
+    let short = backend.append_with_ttl(b"short", 0).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    // Each entry's own TTL overrides the one passed to resolve
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert!(matches!(
+        attacher.try_resolve(&shared_ttl, 1),
+        Err(BlobError::HandleExpired)
+    ));
+    assert_eq!(attacher.resolve(&long, 1).unwrap(), b"long");
+    assert!(matches!(
+        attacher.try_resolve(&short, 30_000),
+        Err(BlobError::HandleExpired)
+    ));
+}
+
+#[test]
+fn test_touch_renews_lease() {
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    let handle = plain.append(b"data").unwrap();
+    assert!(matches!(
+        plain.touch(&handle, 1_000),
+        Err(BlobError::LayoutFeatureRequired("entry_records"))
+    ));
+
+    let ns = test_namespace();
+    let backend = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let renewed = backend.append_with_ttl(b"renewed", 1).unwrap();
+    let pinned = backend.append_with_ttl(b"pinned", 1).unwrap();
+    let lapsed = backend.append_with_ttl(b"lapsed", 1).unwrap();
+
+    // Renewal works from any attached process
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    attacher.touch(&renewed, 60_000).unwrap();
+    attacher.touch(&pinned, TTL_NEVER).unwrap();
+    // A shorter renewal never pulls the expiry back in
+    attacher.touch(&renewed, 0).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    assert_eq!(backend.resolve(&renewed, 30_000).unwrap(), b"renewed");
+    assert_eq!(backend.resolve(&pinned, 30_000).unwrap(), b"pinned");
+    assert!(matches!(
+        attacher.touch(&lapsed, 60_000),
+        Err(BlobError::HandleExpired)
+    ));
+
+    let inline = backend.append_auto(b"tiny").unwrap();
+    assert!(matches!(
+        backend.touch(&inline, 1_000),
+        Err(BlobError::InvalidHandle)
+    ));
+}
+
+// ── Claims ───────────────────────────────────────────────────────────────
+
+#[test]
+fn test_claim_excludes_other_consumers() {
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    let handle = plain.append(b"data").unwrap();
+    assert!(matches!(
+        plain.claim(&handle, 1, 1_000),
+        Err(BlobError::LayoutFeatureRequired("entry_records"))
+    ));
+
+    let ns = test_namespace();
+    let first = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let second = SharedBackend::attach(&ns).unwrap();
+    let h = first.append(b"job").unwrap();
+
+    first.claim(&h, 1, 60_000).unwrap();
+    assert!(matches!(
+        second.claim(&h, 2, 60_000),
+        Err(BlobError::AlreadyClaimed { owner: 1 })
+    ));
+    // The owner may renew, or give the claim up with a zero timeout,
+    // which frees it at once rather than at the next millisecond
+    first.claim(&h, 1, 60_000).unwrap();
+    first.claim(&h, 1, 0).unwrap();
+    second.claim(&h, 2, 60_000).unwrap();
+
+    assert!(second.acknowledge(&h));
+    assert!(matches!(
+        first.claim(&h, 1, 60_000),
+        Err(BlobError::InvalidHandle)
+    ));
+}
+
+#[test]
+fn test_take_expired_claims() {
+    let ns = test_namespace();
+    let creator = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let lapsed = creator.append(b"lapsed").unwrap();
+    let held = creator.append(b"held").unwrap();
+    let acked = creator.append(b"acked").unwrap();
+    let _unclaimed = creator.append(b"unclaimed").unwrap();
+
+    creator.claim(&lapsed, 1, 1).unwrap();
+    creator.claim(&held, 1, 60_000).unwrap();
+    creator.claim(&acked, 1, 1).unwrap();
+    assert!(creator.acknowledge(&acked));
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    // Any attached process can collect them, each lapsed claim once
+    let sweeper = SharedBackend::attach(&ns).unwrap();
+    let redelivered = sweeper.take_expired_claims();
+    assert_eq!(redelivered.len(), 1);
+    let h = redelivered[0];
+    assert_eq!(
+        (h.page_id, h.offset, h.size, h.generation),
+        (
+            lapsed.page_id,
+            lapsed.offset,
+            lapsed.size,
+            lapsed.generation
+        )
+    );
+    assert_eq!(creator.resolve(&h, 30_000).unwrap(), b"lapsed");
+    assert!(creator.take_expired_claims().is_empty());
+
+    // The rebuilt handle can be claimed and acknowledged like the original
+    creator.claim(&h, 2, 60_000).unwrap();
+    assert!(creator.acknowledge(&h));
+    assert!(matches!(
+        creator.claim(&lapsed, 3, 60_000),
+        Err(BlobError::InvalidHandle)
+    ));
+}
+
+// ── Consumer groups ──────────────────────────────────────────────────────
+
+#[test]
+fn test_consumer_groups_ack_independently() {
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    assert!(matches!(
+        plain.register_group("indexer"),
+        Err(BlobError::LayoutFeatureRequired("entry_records"))
+    ));
+
+    let ns = test_namespace();
+    let creator = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let before = creator.append(b"before groups").unwrap();
+    let indexer = creator.register_group("indexer").unwrap();
+
+    // Attachers see the same registry; registering is idempotent
+    let attacher = SharedBackend::attach(&ns).unwrap();
+    assert_eq!(attacher.register_group("indexer").unwrap(), indexer);
+    let archiver = attacher.register_group("archiver").unwrap();
+    assert_ne!(archiver, indexer);
+
+    let h = creator.append(b"fan out").unwrap();
+    let acked = || {
+        let chunks = creator.chunks.read();
+        chunks.get(&0).unwrap().ack_count().load(Ordering::Acquire)
+    };
+
+    // Entries from before any group registered complete on a plain ack
+    assert!(attacher.acknowledge(&before));
+    assert!(attacher.acknowledge(&before));
+    assert_eq!(acked(), 1);
+
+    assert!(attacher.acknowledge_group(&h, indexer));
+    assert!(attacher.acknowledge_group(&h, indexer));
+    assert_eq!(acked(), 1);
+    assert!(creator.acknowledge_group(&h, archiver));
+    assert_eq!(acked(), 2);
+
+    assert!(matches!(
+        creator.try_acknowledge_group(&h, 7),
+        Err(BlobError::UnknownGroup(7))
+    ));
+    assert!(matches!(
+        creator.register_group(""),
+        Err(BlobError::InvalidConfig(_))
+    ));
+    assert!(matches!(
+        creator.register_group(&"x".repeat(MAX_GROUP_NAME_LEN + 1)),
+        Err(BlobError::InvalidConfig(_))
+    ));
+}
+
+#[test]
+fn test_register_group_limit() {
+    let backend =
+        SharedBackend::create_with_config(&test_namespace(), 4096, &records_config()).unwrap();
+    for i in 0..MAX_GROUPS {
+        assert_eq!(
+            backend.register_group(&format!("g{}", i)).unwrap(),
+            i as u32
+        );
+    }
+    assert!(matches!(
+        backend.register_group("one more"),
+        Err(BlobError::TooManyGroups { max: MAX_GROUPS })
+    ));
+    assert_eq!(backend.register_group("g3").unwrap(), 3);
+}
+
+#[test]
+fn test_concurrent_group_registration_agrees() {
+    let ns = test_namespace();
+    let _creator = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let ns = ns.clone();
+            std::thread::spawn(move || {
+                let backend = SharedBackend::attach(&ns).unwrap();
+                ["a", "b", "c"].map(|name| backend.register_group(name).unwrap())
+            })
+        })
+        .collect();
+    let ids: Vec<_> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    assert!(ids.windows(2).all(|w| w[0] == w[1]));
+    assert_eq!(ids[0], [0, 1, 2]);
+}
+
+// ── Reference counts ─────────────────────────────────────────────────────
+
+#[test]
+fn test_retain_release() {
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    let handle = plain.append(b"data").unwrap();
+    assert!(matches!(
+        plain.retain(&handle),
+        Err(BlobError::LayoutFeatureRequired("entry_records"))
+    ));
+
+    let ns = test_namespace();
+    let producer = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let stage = SharedBackend::attach(&ns).unwrap();
+    let frame = producer.append(b"frame").unwrap();
+    let acked = || {
+        let chunks = producer.chunks.read();
+        chunks.get(&0).unwrap().ack_count().load(Ordering::Acquire)
+    };
+
+    // Two stages take references; the producer drops its own
+    stage.retain(&frame).unwrap();
+    stage.retain(&frame).unwrap();
+    producer.release(&frame).unwrap();
+    stage.release(&frame).unwrap();
+    assert_eq!(acked(), 0);
+    assert_eq!(stage.resolve(&frame, 30_000).unwrap(), b"frame");
+
+    stage.release(&frame).unwrap();
+    assert_eq!(acked(), 1);
+    assert!(matches!(
+        stage.release(&frame),
+        Err(BlobError::InvalidHandle)
+    ));
+    assert!(matches!(
+        stage.retain(&frame),
+        Err(BlobError::InvalidHandle)
+    ));
+    // The ack finds no reference left to drop and counts nothing
+    assert!(stage.acknowledge(&frame));
+    assert_eq!(acked(), 1);
+}
+
+#[test]
+fn test_retained_entry_outlives_group_acks() {
+    let backend =
+        SharedBackend::create_with_config(&test_namespace(), 4096, &records_config()).unwrap();
+    let group = backend.register_group("indexer").unwrap();
+    let h = backend.append(b"shared frame").unwrap();
+    let acked = || {
+        let chunks = backend.chunks.read();
+        chunks.get(&0).unwrap().ack_count().load(Ordering::Acquire)
+    };
+
+    backend.retain(&h).unwrap();
+    assert!(backend.acknowledge_group(&h, group));
+    assert_eq!(acked(), 0);
+    backend.release(&h).unwrap();
+    assert_eq!(acked(), 1);
+}
+
+#[test]
+fn test_expired_chunk_reports_dead_letters() {
+    let config = records_config();
+    let chunk_size = CHUNK_HEADER_SIZE + 192;
+    let backend =
+        SharedBackend::create_with_config(&test_namespace(), chunk_size, &config).unwrap();
+    let lost = backend.append(b"lost").unwrap();
+    let acked = backend.append(b"acked").unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    let late = backend.append(b"late").unwrap();
+    // Does not fit behind the first three, so chunk 0 stops being the head
+    let _next = backend.append(&[0u8; 56]).unwrap();
+    assert_eq!((lost.page_id, late.page_id), (0, 0));
+    assert!(backend.acknowledge(&acked));
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert_eq!(backend.cleanup_chunks(1, 60_000), 1);
+
+    // Each entry is aged from its own append, not the chunk's first write
+    let letters = backend.take_dead_letters();
+    assert_eq!(letters.len(), 2);
+    for (letter, original) in letters.iter().zip([lost, late]) {
+        let DeadLetterHandle::Shared(handle) = letter.handle else {
+            panic!("expected a shared handle");
+        };
+        assert_eq!((handle.page_id, handle.offset), (0, original.offset));
+        assert_eq!(handle.timestamp, original.timestamp);
+        assert_eq!(letter.size, 4);
+        assert!(letter.age_ms >= 5);
+    }
+    assert!(letters[0].age_ms >= letters[1].age_ms + 20);
+    assert!(backend.take_dead_letters().is_empty());
+}
+
+#[test]
+fn test_expired_chunk_without_records_reports_itself() {
+    let chunk_size = CHUNK_HEADER_SIZE + 64;
+    let backend = SharedBackend::create(&test_namespace(), chunk_size, None).unwrap();
+    let first = backend.append(b"lost").unwrap();
+    backend.append(b"also lost").unwrap();
+    let acked = backend.append(b"acked").unwrap();
+    let _next = backend.append(&[0u8; 60]).unwrap();
+    assert_eq!(first.page_id, 0);
+    assert!(backend.acknowledge(&acked));
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert_eq!(backend.cleanup_chunks(1, 60_000), 1);
+
+    // The entries cannot be told apart, so the chunk stands for them
+    let letters = backend.take_dead_letters();
+    assert_eq!(letters.len(), 1);
+    assert_eq!(
+        letters[0].handle,
+        DeadLetterHandle::SharedChunk {
+            page_id: 0,
+            generation: first.generation,
+            unacked: 2,
+        }
+    );
+    assert_eq!(letters[0].entries(), 2);
+    assert_eq!(letters[0].size, 18);
+    assert!(letters[0].age_ms >= 5);
+}
+
+#[test]
+fn test_delete_and_purge() {
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    let handle = plain.append(b"data").unwrap();
+    assert!(matches!(
+        plain.delete(&handle),
+        Err(BlobError::LayoutFeatureRequired("entry_records"))
+    ));
+
+    let ns = test_namespace();
+    let producer = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let _audit = producer.register_group("audit").unwrap();
+    let wrong = producer.append(b"wrong").unwrap();
+    let secret = producer.append(b"secret").unwrap();
+    let acked = || {
+        let chunks = producer.chunks.read();
+        chunks.get(&0).unwrap().ack_count().load(Ordering::Acquire)
+    };
+
+    let consumer = SharedBackend::attach(&ns).unwrap();
+    consumer.retain(&wrong).unwrap();
+    producer.delete(&wrong).unwrap();
+    assert!(matches!(
+        consumer.try_resolve(&wrong, 30_000),
+        Err(BlobError::HandleExpired)
+    ));
+    assert!(matches!(
+        consumer.touch(&wrong, 60_000),
+        Err(BlobError::HandleExpired)
+    ));
+    assert!(matches!(
+        consumer.release(&wrong),
+        Err(BlobError::InvalidHandle)
+    ));
+
+    consumer.purge(&secret).unwrap();
+    let chunk = producer.get_or_map_chunk(0).unwrap();
+    let payload = unsafe {
+        std::slice::from_raw_parts(
+            chunk.data_ptr().add(secret.offset as usize),
+            secret.size as usize,
+        )
+    };
+    assert!(payload.iter().all(|&b| b == 0));
+
+    // Both count as acked although the group never acked them
+    assert_eq!(acked(), 2);
+    producer.delete(&wrong).unwrap();
+    assert_eq!(acked(), 2);
+
+    let inline = producer.append_auto(b"tiny").unwrap();
+    assert!(matches!(
+        producer.delete(&inline),
+        Err(BlobError::InvalidHandle)
+    ));
+}
+
+// ── Slices ───────────────────────────────────────────────────────────────
+
+#[test]
+fn test_slices_hold_their_entry() {
+    let ns = test_namespace();
+    let producer = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let consumer = SharedBackend::attach(&ns).unwrap();
+    let _before = producer.append(b"earlier entry").unwrap();
+    let container = producer.append(b"headerbody-onebody-two").unwrap();
+    let acked = || {
+        let chunks = producer.chunks.read();
+        chunks.get(&0).unwrap().ack_count().load(Ordering::Acquire)
+    };
+
+    let one = container.slice(6..14).unwrap();
+    let two = container.slice(14..).unwrap();
+    assert_eq!(one.offset, container.offset + 6);
+    assert_eq!(two.size, 8);
+    assert_eq!(consumer.resolve(&one, 30_000).unwrap(), b"body-one");
+    assert_eq!(consumer.resolve(&two, 30_000).unwrap(), b"body-two");
+    assert_eq!(one.slice(5..).unwrap(), container.slice(11..14).unwrap());
+    assert!(container.slice(20..30).is_none());
+    assert_eq!(container.slice(..).unwrap(), container);
+
+    // The entry outlives its own ack until every retained slice is
+    // released; acking a slice, however often, drops no reference
+    producer.retain(&one).unwrap();
+    producer.retain(&two).unwrap();
+    assert!(producer.acknowledge(&container));
+    consumer.release(&one).unwrap();
+    assert!(consumer.acknowledge(&two));
+    assert!(consumer.acknowledge(&two));
+    assert_eq!(acked(), 0);
+    assert_eq!(
+        consumer.resolve(&container, 30_000).unwrap(),
+        b"headerbody-onebody-two"
+    );
+    consumer.touch(&two, 60_000).unwrap();
+    consumer.release(&two).unwrap();
+    assert_eq!(acked(), 1);
+
+    // Only the entry's own handle deletes it
+    let other = producer.append(b"another-one").unwrap();
+    assert!(matches!(
+        producer.delete(&other.slice(..7).unwrap()),
+        Err(BlobError::InvalidHandle)
+    ));
+    producer.delete(&other).unwrap();
+    assert!(matches!(
+        consumer.try_resolve(&other.slice(..7).unwrap(), 30_000),
+        Err(BlobError::HandleExpired)
+    ));
+
+    // A slice reaching past its entry is no slice of it
+    let forged = OverflowHandle {
+        size: 12,
+        ..other.slice(8..).unwrap()
+    };
+    assert!(matches!(
+        consumer.try_resolve(&forged, 30_000),
+        Err(BlobError::InvalidHandle)
+    ));
+
+    let inline = OverflowHandle::inline(b"tiny").unwrap();
+    assert_eq!(inline.slice(1..3).unwrap().inline_data().unwrap(), b"in");
+}
+
+#[test]
+fn test_slices_of_authenticated_handles() {
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            entry_records: true,
+            authenticated_handles: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let ns = test_namespace();
+    let producer = SharedBackend::create_with_config(&ns, 4096, &config).unwrap();
+    let consumer = SharedBackend::attach(&ns).unwrap();
+    let handle = producer.append(b"headerbody").unwrap();
+
+    // The backend tags the slice itself; anyone can set the marker
+    let body = producer.slice(&handle, 6..).unwrap();
+    assert_ne!(body.tag, handle.tag);
+    assert_eq!(consumer.resolve(&body, 30_000).unwrap(), b"body");
+    assert_eq!(
+        consumer.resolve(&consumer.slice(&body, 1..3).unwrap(), 30_000),
+        Some(b"od".to_vec())
+    );
+    assert!(matches!(
+        consumer.try_resolve(&handle.slice(6..).unwrap(), 30_000),
+        Err(BlobError::InvalidHandle)
+    ));
+
+    // The tag covers where the slice points
+    let other = producer.append(b"another entry").unwrap();
+    let moved = OverflowHandle {
+        offset: other.offset,
+        ..body
+    };
+    assert!(matches!(
+        consumer.try_resolve(&moved, 30_000),
+        Err(BlobError::InvalidHandle)
+    ));
+
+    // Handles that do not verify are rejected before any chunk is mapped
+    producer.append(&[0u8; 3900]).unwrap();
+    let forged = OverflowHandle {
+        page_id: 1,
+        tag: body.tag ^ 1,
+        ..body
+    };
+    let mapped = consumer.chunk_count();
+    assert!(matches!(
+        consumer.try_resolve(&forged, 30_000),
+        Err(BlobError::InvalidHandle)
+    ));
+    assert_eq!(consumer.chunk_count(), mapped);
+
+    // Without entry records there is no entry to tie a slice to
+    let plain = SharedBackend::create(&test_namespace(), 4096, None).unwrap();
+    let whole = plain.append(b"headerbody").unwrap();
+    assert!(matches!(
+        plain.try_resolve(&whole.slice(6..).unwrap(), 30_000),
+        Err(BlobError::InvalidHandle)
+    ));
+    assert!(matches!(
+        plain.slice(&whole, 6..),
+        Err(BlobError::LayoutFeatureRequired(_))
+    ));
+}
+
+// ── Aligned appends ──────────────────────────────────────────────────────
+
+#[test]
+fn test_append_aligned() {
+    let ns = test_namespace();
+    let backend = SharedBackend::create(&ns, 16 * 1024, None).unwrap();
+
+    // Offsets are aligned from the page-aligned start of the chunk
+    let packed = backend.append(b"abc").unwrap();
+    let aligned = backend.append_aligned(b"simd", 64).unwrap();
+    let page = backend.append_aligned(b"dio", MAX_ALIGN).unwrap();
+    assert_eq!(packed.offset, 0);
+    assert_eq!((CHUNK_HEADER_SIZE as u64 + aligned.offset) % 64, 0);
+    assert_eq!(
+        (CHUNK_HEADER_SIZE as u64 + page.offset) % MAX_ALIGN as u64,
+        0
+    );
+    assert_eq!(backend.resolve(&aligned, 30_000).unwrap(), b"simd");
+    assert_eq!(backend.resolve(&page, 30_000).unwrap(), b"dio");
+
+    assert!(matches!(
+        backend.append_aligned(b"x", 24),
+        Err(BlobError::InvalidAlignment(24))
+    ));
+    assert!(matches!(
+        backend.append_aligned(b"x", 2 * MAX_ALIGN),
+        Err(BlobError::InvalidAlignment(_))
+    ));
+    // The padding an empty chunk needs counts against the largest entry
+    let room = backend.data_capacity() - (MAX_ALIGN - CHUNK_HEADER_SIZE);
+    assert!(matches!(
+        backend.append_aligned(&vec![0u8; room + 1], MAX_ALIGN),
+        Err(BlobError::DataTooLarge { max, .. }) if max == room
+    ));
+}
+
+#[test]
+fn test_append_aligned_with_entry_records() {
+    let ns = test_namespace();
+    let config = SharedConfig {
+        align: 16,
+        ..records_config()
+    };
+    let backend = SharedBackend::create_with_config(&ns, 4096, &config).unwrap();
+
+    // The record sits in front of the aligned payload; the gap before the
+    // record holds a filler entry
+    let h1 = backend.append(b"odd").unwrap();
+    let h2 = backend.append_aligned(b"wide", 64).unwrap();
+    assert_eq!((h1.offset, h2.offset), (48, 192));
+    // Reservations take the default alignment. Their gap of 8 bytes is too
+    // small for a filler, so it is widened until one fits.
+    let mut slot = backend.reserve(8).unwrap();
+    assert_eq!(slot.as_ptr() as usize % 16, 0);
+    slot.copy_from_slice(b"reserved");
+    let h3 = slot.commit().unwrap();
+    assert_eq!(h3.offset, 304);
+
+    // Records are still walked in order, past the fillers
+    let part = h2.slice(1..3).unwrap();
+    assert_eq!(backend.resolve(&part, 30_000).unwrap(), b"id");
+    backend.retain(&h3).unwrap();
+    backend.release(&h3).unwrap();
+
+    // Fillers count as entries that are acknowledged already
+    for handle in [&h1, &h2, &h3] {
+        assert!(backend.acknowledge(handle));
+    }
+    let chunk = backend.get_or_map_chunk(0).unwrap();
+    assert_eq!(chunk.entry_count().load(Ordering::Acquire), 5);
+    assert_eq!(chunk.ack_count().load(Ordering::Acquire), 5);
+    assert_eq!(backend.take_dead_letters().len(), 0);
+}
+
+// ── Typed values ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_append_value_and_view() {
+    let ns = test_namespace();
+    let backend = SharedBackend::create_with_config(&ns, 4096, &records_config()).unwrap();
+    let attacher = SharedBackend::attach(&ns).unwrap();
+
+    // Values are aligned for their type, and read in any attached process
+    backend.append(b"abc").unwrap();
+    let h = backend.append_value(&[1.5f64, -2.0]).unwrap();
+    assert_eq!(h.raw().offset % 8, 0);
+    for b in [&backend, &attacher] {
+        let v = b.view(&h, 30_000).unwrap();
+        assert_eq!(*v, [1.5, -2.0]);
+    }
+
+    // A view is a copy, which a purge does not touch
+    let purged = backend.append_value(&[3.5f64, 4.0]).unwrap();
+    let v = attacher.view(&purged, 30_000).unwrap();
+    backend.purge(purged.raw()).unwrap();
+    assert_eq!(v.into_inner(), [3.5, 4.0]);
+
+    // Misaligned slices and inline handles read as well
+    let raw = backend.append(b"0123456789").unwrap();
+    let slice = Handle::<u32, OverflowHandle>::from_raw(raw.slice(1..5).unwrap()).unwrap();
+    let v = backend.view(&slice, 30_000).unwrap();
+    assert_eq!(*v, u32::from_ne_bytes(*b"1234"));
+    let inline = backend.append_auto(&7u64.to_ne_bytes()).unwrap();
+    assert!(inline.is_inline());
+    let v = backend.view(
+        &Handle::<u64, OverflowHandle>::from_raw(inline).unwrap(),
+        30_000,
+    );
+    assert_eq!(*v.unwrap(), 7);
+
+    assert!(Handle::<u64, OverflowHandle>::from_raw(raw).is_none());
+    backend.delete(h.raw()).unwrap();
+    assert!(matches!(
+        attacher.view(&h, 30_000),
+        Err(BlobError::HandleExpired)
+    ));
+
+    #[derive(Clone, Copy)]
+    #[repr(C, align(8192))]
+    struct Huge([u8; 8192]);
+    unsafe impl Pod for Huge {}
+    assert!(matches!(
+        backend.append_value(&Huge([0; 8192])),
+        Err(BlobError::InvalidAlignment(8192))
+    ));
+}
+
 // ── Validation ───────────────────────────────────────────────────────────
 
 #[test]
@@ -39,14 +39,18 @@ impl LifecycleManager {
     ///   the decay timeout has elapsed.
     /// - **Shared cleanup** (`cleanup_shared`): sweeps all shared-memory
     ///   chunks, recycling those that are fully acked past the decay window.
+    /// - **Redelivery** (`redeliver_expired_claims`): queues shared entries
+    ///   whose claim lapsed without an ack (arenas with entry records).
     ///
     /// Returns the total number of pages/chunks freed (heap + shared).
     ///
-    /// Time: O(p + c) where p = heap pages, c = shared chunks.
+    /// Time: O(p + c) where p = heap pages, c = shared chunks, plus
+    /// O(e) over unacknowledged shared entries in arenas with entry records.
     pub fn maintenance_cycle(&self) -> usize {
         if let Some(store) = self.store.upgrade() {
             let heap_freed = store.cleanup_acknowledged();
             let shared_freed = store.cleanup_shared();
+            store.redeliver_expired_claims();
             heap_freed + shared_freed
         } else {
             0
@@ -4,7 +4,7 @@
 //!
 //! 1. **Acknowledgement-based cleanup** — chunks are freed after all entries
 //!    are acked and the decay timeout elapses.
-//! 2. **TTL-based expiry** — chunks are freed when their oldest data exceeds
+//! 2. **TTL-based expiry** — chunks are freed when their newest data exceeds
 //!    the TTL, even if no entries were acknowledged (consumer crash scenario).
 //! 3. **Proactive prefetch** — the next chunk is pre-allocated when the active
 //!    chunk crosses the prefetch threshold (80%), eliminating allocation
@@ -15,13 +15,16 @@
 //! - Background `LifecycleManager` thread integration
 //! - Concurrent append + cleanup safety
 //! - Write-after-cleanup correctness (new data into fresh chunks)
+//! - Idle write-head rotation (a drained head chunk is eventually freed)
+//! - Consumer groups (a chunk waits for every group's acks)
+//! - Redelivery of entries whose claim lapsed
 //!
 //! Run with `cargo test -- --nocapture` to see the print statements.
 
 use crate::backend::shared::{SharedBackend, CHUNK_HEADER_SIZE};
 use crate::lifecycle::{BlobStoreLifecycleExt, LifecycleManager};
 use crate::page::PinnedBlobStore;
-use crate::types::{Config, OverflowHandle};
+use crate::types::{ArenaLayout, Config, OverflowHandle, SharedConfig, TTL_NEVER};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
@@ -159,6 +162,47 @@ fn test_decay_timeout_prevents_premature_free() {
     println!("[PASS] Decay timeout prevents premature freeing\n");
 }
 
+#[test]
+fn test_chunk_waits_for_every_consumer_group() {
+    println!("\n=== test_chunk_waits_for_every_consumer_group ===");
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            entry_records: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let chunk_size = CHUNK_HEADER_SIZE + 256;
+    let backend = SharedBackend::create_with_config(&ns(), chunk_size, &config).unwrap();
+    let indexer = backend.register_group("indexer").unwrap();
+    let archiver = backend.register_group("archiver").unwrap();
+    println!(
+        "[1] Registered groups indexer={} archiver={}",
+        indexer, archiver
+    );
+
+    let h0 = backend.append(&[0xAAu8; 150]).unwrap();
+    let _h1 = backend.append(&[0xBBu8; 150]).unwrap();
+    println!("[2] Wrote to chunk {} and overflowed", h0.page_id);
+
+    // The indexer acks twice; only the first counts, and the archiver is still owed one
+    assert!(backend.acknowledge_group(&h0, indexer));
+    assert!(backend.acknowledge_group(&h0, indexer));
+    let freed = backend.cleanup_chunks(30_000, 0);
+    println!(
+        "[3] Indexer acked: freed {} (archiver still pending)",
+        freed
+    );
+    assert_eq!(freed, 0);
+    assert_eq!(backend.resolve(&h0, 30_000).unwrap(), vec![0xAAu8; 150]);
+
+    assert!(backend.acknowledge_group(&h0, archiver));
+    let freed = backend.cleanup_chunks(30_000, 0);
+    println!("[4] Archiver acked: freed {}", freed);
+    assert_eq!(freed, 1);
+    println!("[PASS] Chunk freed only after every group acked\n");
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // 2. TTL-BASED EXPIRY (CONSUMER CRASH SCENARIO)
 // ═══════════════════════════════════════════════════════════════════════════
@@ -185,13 +229,126 @@ fn test_ttl_expiry_frees_unacked_chunks() {
         "[3] cleanup_chunks(ttl=1ms, decay=0ms) -> freed {} chunks",
         freed
     );
-    println!("    This works because first_write_ts was stamped on append,");
-    println!("    and now - first_write_ts > 1ms TTL -> expired!");
+    println!("    This works because last_write_ts was stamped on append,");
+    println!("    and now - last_write_ts > 1ms TTL -> expired!");
 
     assert!(freed >= 1);
     println!("[PASS] TTL-expired unacked chunks were freed (consumer crash recovery)\n");
 }
 
+#[test]
+fn test_ttl_measured_from_last_write_on_slow_fill() {
+    println!("\n=== test_ttl_measured_from_last_write_on_slow_fill ===");
+    let ns = ns();
+    let chunk_size = CHUNK_HEADER_SIZE + 128;
+    let backend = SharedBackend::create(&ns, chunk_size, None).unwrap();
+    let ttl_ms = 500;
+
+    let h0 = backend.append(&[0xAAu8; 40]).unwrap();
+    println!("[1] First entry on chunk {}", h0.page_id);
+    thread::sleep(Duration::from_millis(400));
+    let h1 = backend.append(&[0xBBu8; 40]).unwrap();
+    println!("[2] 400ms later, second entry on chunk {}", h1.page_id);
+    assert_eq!(h1.page_id, h0.page_id);
+
+    let h2 = backend.append(&[0xCCu8; 100]).unwrap();
+    println!("[3] Overflow moved the write head to chunk {}", h2.page_id);
+    assert_ne!(h2.page_id, h0.page_id);
+
+    thread::sleep(Duration::from_millis(200));
+    let freed = backend.cleanup_chunks(ttl_ms, 0);
+    println!(
+        "[4] ~600ms after the first write, ~200ms after the last: freed {}",
+        freed
+    );
+    assert_eq!(freed, 0);
+    assert_eq!(backend.resolve(&h1, ttl_ms).unwrap(), vec![0xBBu8; 40]);
+
+    thread::sleep(Duration::from_millis(400));
+    let freed = backend.cleanup_chunks(ttl_ms, 0);
+    println!("[5] Once the last write is past the TTL: freed {}", freed);
+    assert_eq!(freed, 1);
+    println!("[PASS] Slowly filled chunk kept until its newest entry expired\n");
+}
+
+#[test]
+fn test_per_entry_ttl_governs_chunk_expiry() {
+    println!("\n=== test_per_entry_ttl_governs_chunk_expiry ===");
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            entry_records: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let chunk_size = CHUNK_HEADER_SIZE + 256;
+    let backend = SharedBackend::create_with_config(&ns(), chunk_size, &config).unwrap();
+
+    // Chunk 0: short-lived telemetry; chunk 1: a long-lived control payload
+    let telemetry = backend.append_with_ttl(&[0xAAu8; 150], 1).unwrap();
+    let control = backend.append_with_ttl(&[0xBBu8; 150], 60_000).unwrap();
+    let head = backend.append(&[0xCCu8; 150]).unwrap();
+    println!(
+        "[1] telemetry (1ms) -> chunk {}, control (60s) -> chunk {}, head -> chunk {}",
+        telemetry.page_id, control.page_id, head.page_id
+    );
+    assert_eq!(
+        [telemetry.page_id + 1, control.page_id + 1],
+        [control.page_id, head.page_id]
+    );
+
+    thread::sleep(Duration::from_millis(5));
+    let freed = backend.cleanup_chunks(30_000, 0);
+    println!(
+        "[2] Shared TTL 30s, after 5ms: freed {} (telemetry chunk)",
+        freed
+    );
+    assert_eq!(freed, 1);
+    assert!(backend.resolve(&telemetry, 30_000).is_none());
+
+    let freed = backend.cleanup_chunks(1, 0);
+    println!(
+        "[3] Shared TTL 1ms: freed {} (control entry still live)",
+        freed
+    );
+    assert_eq!(freed, 0);
+    assert_eq!(backend.resolve(&control, 1).unwrap(), vec![0xBBu8; 150]);
+    println!("[PASS] Chunks expire with the latest own expiry of their entries\n");
+}
+
+#[test]
+fn test_pinned_entry_keeps_chunk_until_acked() {
+    println!("\n=== test_pinned_entry_keeps_chunk_until_acked ===");
+    let config = SharedConfig {
+        layout: ArenaLayout {
+            entry_records: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let chunk_size = CHUNK_HEADER_SIZE + 256;
+    let backend = SharedBackend::create_with_config(&ns(), chunk_size, &config).unwrap();
+
+    // Chunk 0 holds one in-flight job; the next append moves the head on
+    let job = backend.append(&[0xAAu8; 150]).unwrap();
+    let head = backend.append(&[0xBBu8; 150]).unwrap();
+    assert_eq!(job.page_id + 1, head.page_id);
+    backend.touch(&job, TTL_NEVER).unwrap();
+    println!("[1] job pinned in chunk {}", job.page_id);
+
+    thread::sleep(Duration::from_millis(5));
+    let freed = backend.cleanup_chunks(1, 0);
+    println!("[2] Shared TTL 1ms, after 5ms: freed {}", freed);
+    assert_eq!(freed, 0);
+    assert_eq!(backend.resolve(&job, 1).unwrap(), vec![0xAAu8; 150]);
+
+    assert!(backend.acknowledge(&job));
+    let freed = backend.cleanup_chunks(1, 0);
+    println!("[3] After ack: freed {}", freed);
+    assert_eq!(freed, 1);
+    println!("[PASS] Pinned entry outlives the TTL and goes away on ack\n");
+}
+
 #[test]
 fn test_ttl_does_not_expire_fresh_data() {
     println!("\n=== test_ttl_does_not_expire_fresh_data ===");
@@ -512,12 +669,54 @@ fn test_lifecycle_ttl_expiry_via_manager() {
         "[3] maintenance_cycle() -> freed {} chunks via TTL expiry",
         freed
     );
-    println!("    cleanup_shared() saw first_write_ts is > 1ms old -> expired!");
+    println!("    cleanup_shared() saw last_write_ts is > 1ms old -> expired!");
 
     assert!(freed >= 1);
     println!("[PASS] LifecycleManager triggers TTL-based expiry for unacked data\n");
 }
 
+#[test]
+fn test_lifecycle_redelivers_lapsed_claims() {
+    println!("\n=== test_lifecycle_redelivers_lapsed_claims ===");
+    let shared_config = SharedConfig {
+        layout: ArenaLayout {
+            entry_records: true,
+            ..ArenaLayout::default()
+        },
+        ..SharedConfig::default()
+    };
+    let store = Arc::new(
+        PinnedBlobStore::new_shared_with_config(Config::default(), &ns(), 4096, shared_config)
+            .unwrap(),
+    );
+
+    let job = store.append_shared(b"job payload").unwrap();
+    let done = store.append_shared(b"finished job").unwrap();
+    store.claim_shared(&job, 1, 1).unwrap();
+    store.claim_shared(&done, 1, 1).unwrap();
+    assert!(store.acknowledge_shared(&done));
+    println!("[1] Consumer 1 claimed two jobs for 1ms and finished one");
+
+    thread::sleep(Duration::from_millis(5));
+    let manager = LifecycleManager::new(&store);
+    manager.maintenance_cycle();
+
+    let redelivered = store.next_redelivery().unwrap();
+    println!(
+        "[2] maintenance_cycle() redelivered offset {} (the unfinished job)",
+        redelivered.offset
+    );
+    assert_eq!(redelivered.offset, job.offset);
+    assert_eq!(store.resolve(&redelivered).unwrap(), b"job payload");
+    assert!(store.next_redelivery().is_none());
+
+    // Consumer 2 picks it up; the lapsed claim is not redelivered twice
+    store.claim_shared(&redelivered, 2, 60_000).unwrap();
+    manager.maintenance_cycle();
+    assert!(store.next_redelivery().is_none());
+    println!("[PASS] Lapsed claims are redelivered once through the store\n");
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // 6. CONCURRENT APPEND + CLEANUP SAFETY
 // ═══════════════════════════════════════════════════════════════════════════
@@ -623,3 +822,109 @@ fn test_cleanup_does_not_corrupt_active_chunk() {
     assert!(data.len() == 50);
     println!("[PASS] Active chunk is protected from cleanup\n");
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 7. IDLE WRITE-HEAD ROTATION
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_idle_head_is_rotated_and_freed() {
+    println!("\n=== test_idle_head_is_rotated_and_freed ===");
+    let ns = ns();
+    let chunk_size = CHUNK_HEADER_SIZE + 256;
+    let backend = SharedBackend::create(&ns, chunk_size, None).unwrap();
+
+    let h1 = backend.append(&[0xAAu8; 50]).unwrap();
+    let h2 = backend.append(&[0xBBu8; 50]).unwrap();
+    backend.acknowledge(&h1);
+    backend.acknowledge(&h2);
+    println!(
+        "[1] Burst of 2 entries on head chunk {}, both acked",
+        h1.page_id
+    );
+
+    assert_eq!(backend.cleanup_chunks(30_000, 0), 0);
+    println!("[2] First sweep only notes the head as idle");
+
+    assert_eq!(backend.cleanup_chunks(30_000, 0), 0);
+    let h3 = backend.append(&[0xCCu8; 50]).unwrap();
+    println!(
+        "[3] Second sweep sealed chunk {} -> next append lands on chunk {}",
+        h1.page_id, h3.page_id
+    );
+    assert_ne!(h3.page_id, h1.page_id);
+
+    let freed = backend.cleanup_chunks(30_000, 0);
+    println!("[4] Third sweep freed {} chunk(s)", freed);
+    assert_eq!(freed, 1);
+    assert!(backend.resolve(&h1, 30_000).is_none());
+    assert_eq!(backend.resolve(&h3, 30_000).unwrap(), vec![0xCCu8; 50]);
+    println!("[PASS] Drained head chunk was rotated out and reclaimed\n");
+}
+
+#[test]
+fn test_busy_head_is_not_rotated() {
+    println!("\n=== test_busy_head_is_not_rotated ===");
+    let ns = ns();
+    let chunk_size = CHUNK_HEADER_SIZE + 256;
+    let backend = SharedBackend::create(&ns, chunk_size, None).unwrap();
+
+    let h1 = backend.append(&[0xAAu8; 50]).unwrap();
+    backend.acknowledge(&h1);
+    backend.cleanup_chunks(30_000, 0);
+    println!("[1] Head chunk {} drained and noted idle", h1.page_id);
+
+    let h2 = backend.append(&[0xBBu8; 50]).unwrap();
+    backend.acknowledge(&h2);
+    backend.cleanup_chunks(30_000, 0);
+    println!("[2] New entry appended and acked before the next sweep");
+
+    let h3 = backend.append(&[0xCCu8; 50]).unwrap();
+    println!("[3] Next append -> chunk {}", h3.page_id);
+    assert_eq!(h3.page_id, h1.page_id);
+
+    backend.acknowledge(&h3);
+    let freed = backend.cleanup_chunks(30_000, 60_000);
+    println!("[4] Sweep with a 60s decay timeout -> freed {}", freed);
+    assert_eq!(freed, 0);
+    assert_eq!(backend.append(&[0xDDu8; 50]).unwrap().page_id, h1.page_id);
+    println!("[PASS] Head that keeps receiving data stays the head\n");
+}
+
+#[test]
+fn test_append_racing_rotation_stays_readable() {
+    println!("\n=== test_append_racing_rotation_stays_readable ===");
+    let ns = ns();
+    let chunk_size = CHUNK_HEADER_SIZE + 4096;
+    let backend = Arc::new(SharedBackend::create(&ns, chunk_size, None).unwrap());
+
+    // The writer acks everything, so the head is drained between rounds
+    // and a decay-0 sweeper keeps sealing it under the writer's feet.
+    let writer = {
+        let backend = Arc::clone(&backend);
+        thread::spawn(move || {
+            let mut chunks = Vec::new();
+            for i in 0..2000u32 {
+                let h = backend.append(&i.to_le_bytes()).unwrap();
+                assert_eq!(backend.resolve(&h, 30_000).unwrap(), i.to_le_bytes());
+                backend.acknowledge(&h);
+                chunks.push(h.page_id);
+                thread::yield_now();
+            }
+            chunks
+        })
+    };
+    let mut freed = 0;
+    while !writer.is_finished() {
+        freed += backend.cleanup_chunks(30_000, 0);
+        thread::yield_now();
+    }
+    let mut chunks = writer.join().unwrap();
+    chunks.dedup();
+    println!(
+        "[1] 2000 rounds used {} head chunk(s), {} freed by the sweeper",
+        chunks.len(),
+        freed
+    );
+    println!("[PASS] Every entry was readable until it was acked\n");
+}
@@ -36,6 +36,7 @@
 //! atomically, so concurrent readers see a clean generation mismatch rather
 //! than corrupted data.
 
+#[allow(clippy::module_inception)]
 pub mod lifecycle;
 
 pub use lifecycle::*;
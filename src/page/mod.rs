@@ -21,6 +21,7 @@
 //! - Free-page recycling: `Mutex<BinaryHeap<Reverse<u32>>>` (min-heap)
 //! - Generation counter: `AtomicU32` (prevents ABA on recycled page IDs)
 
+#[allow(clippy::module_inception)]
 pub(crate) mod page;
 mod store;
 
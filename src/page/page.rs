@@ -1,5 +1,27 @@
-use crate::types::{now_ms, BlobError, Result};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::types::{
+    copy_gathered, gathered_len, now_ms, BlobError, BlobHandle, DeadLetter, DeadLetterHandle,
+    Result, MAX_ALIGN,
+};
+use std::io::IoSlice;
+use std::sync::atomic::{fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+
+/// `expires_at` of a deleted entry: expired long ago, and never renewed
+const DELETED: u64 = 1;
+
+/// `expires_at` of a reserved entry that is not committed yet: it cannot
+/// be read, but pins its page until it is committed or abandoned
+const PENDING: u64 = 2;
+
+/// Which part of a blob an entry holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EntryKind {
+    /// A whole single-page blob
+    Whole,
+    /// First piece of a multi-page blob
+    Head { end_page_id: u32, total_size: u64 },
+    /// Later piece of a multi-page blob
+    Continuation,
+}
 
 /// Metadata for a single entry within a page
 #[derive(Debug)]
@@ -7,41 +29,99 @@ pub(crate) struct EntryMetadata {
     /// Offset within the page
     pub offset: u32,
 
-    /// Size of the entry (retained for Debug output)
-    pub _size: u32,
+    /// Size of the entry
+    pub size: u32,
 
     /// Creation timestamp
     pub timestamp: u64,
 
-    /// Whether this entry has been acknowledged
+    /// Whether this entry has been acknowledged (which drops its initial
+    /// reference)
     pub acknowledged: AtomicBool,
+
+    /// Live references; starts at 1 and the entry is done at 0
+    pub refs: AtomicU32,
+
+    /// Own expiry (ms since UNIX epoch); 0 means the store's default TTL.
+    /// `DELETED` and `PENDING` mark deleted and reserved entries.
+    pub expires_at: AtomicU64,
+
+    /// Which part of its blob this entry holds
+    pub kind: EntryKind,
+
+    /// Purged while its page was pinned; zeroed once the last pin goes
+    zero_pending: AtomicBool,
 }
 
 impl EntryMetadata {
-    fn new(offset: u32, size: u32) -> Self {
+    fn new(offset: u32, size: u32, expires_at: u64, kind: EntryKind) -> Self {
         let timestamp = now_ms();
 
         Self {
             offset,
-            _size: size,
+            size,
+            kind,
             timestamp,
             acknowledged: AtomicBool::new(false),
+            refs: AtomicU32::new(1),
+            expires_at: AtomicU64::new(expires_at),
+            zero_pending: AtomicBool::new(false),
         }
     }
 
-    /// Check if this entry has expired
+    /// Check if this entry has expired: past its own expiry if it has one,
+    /// otherwise older than `ttl_ms`
     pub fn is_expired(&self, ttl_ms: u64) -> bool {
-        now_ms().saturating_sub(self.timestamp) > ttl_ms
+        match self.expires_at.load(Ordering::Acquire) {
+            0 => now_ms().saturating_sub(self.timestamp) > ttl_ms,
+            PENDING => false,
+            expires_at => now_ms() > expires_at,
+        }
+    }
+
+    /// Check if the entry is reserved but not committed yet
+    pub fn is_pending(&self) -> bool {
+        self.expires_at.load(Ordering::Acquire) == PENDING
     }
 
-    /// Mark this entry as acknowledged
+    /// Mark this entry as acknowledged, dropping its initial reference the
+    /// first time
     pub fn acknowledge(&self) {
+        if !self.acknowledged.swap(true, Ordering::AcqRel) {
+            self.release();
+        }
+    }
+
+    /// Take another reference. Fails once the count has reached zero or
+    /// the entry has expired.
+    pub fn retain(&self, ttl_ms: u64) -> bool {
+        !self.is_expired(ttl_ms)
+            && self
+                .refs
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                    (n > 0).then(|| n.saturating_add(1))
+                })
+                .is_ok()
+    }
+
+    /// Drop a reference. Fails if the count is already zero.
+    pub fn release(&self) -> bool {
+        self.refs
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    /// Expire the entry for good and drop every reference, so it can no
+    /// longer be read and counts as done
+    pub fn delete(&self) {
+        self.expires_at.store(DELETED, Ordering::Release);
         self.acknowledged.store(true, Ordering::Release);
+        self.refs.store(0, Ordering::Release);
     }
 
-    /// Check if acknowledged or expired
+    /// Check if every reference is gone or the entry expired
     pub fn should_cleanup(&self, ttl_ms: u64) -> bool {
-        self.acknowledged.load(Ordering::Acquire) || self.is_expired(ttl_ms)
+        self.refs.load(Ordering::Acquire) == 0 || self.is_expired(ttl_ms)
     }
 }
 
@@ -50,12 +130,22 @@ pub(crate) struct Page {
     /// Unique page identifier
     pub id: u32,
 
-    /// The actual data buffer
+    /// The actual data buffer, with room to start the page at a
+    /// `MAX_ALIGN` boundary
     data: Box<[u8]>,
 
+    /// Where the page starts within `data`
+    start: usize,
+
+    /// Usable size of the page
+    capacity: usize,
+
     /// Current number of bytes used (atomic for lock-free append)
     used: AtomicUsize,
 
+    /// Bytes of `used` skipped to align payloads
+    padding: AtomicUsize,
+
     /// Generation counter for handle validation
     pub generation: u32,
 
@@ -64,6 +154,13 @@ pub(crate) struct Page {
 
     /// Timestamp when this page became empty (for decay tracking)
     empty_since: AtomicUsize, // 0 means not empty, otherwise timestamp in ms
+
+    /// References into `data` handed out by `view`; the page neither
+    /// decays nor has bytes zeroed while any are held
+    pins: AtomicUsize,
+
+    /// Some entry has `zero_pending` set
+    purge_pending: AtomicBool,
 }
 
 impl Page {
@@ -73,6 +170,9 @@ impl Page {
     /// 1. We track `used` atomically
     /// 2. Only written regions are ever read
     /// 3. Writes happen before reads via `used` counter
+    ///
+    /// The page starts at a `MAX_ALIGN` boundary, so an offset aligned
+    /// within the page is aligned in memory too.
     pub fn new(id: u32, size: usize, generation: u32) -> Self {
         // PERFORMANCE: Use MaybeUninit to skip zeroing
         // This is safe because:
@@ -81,9 +181,9 @@ impl Page {
         // - The `used` atomic ensures proper ordering
         use std::mem::MaybeUninit;
 
-        let mut uninit_vec: Vec<MaybeUninit<u8>> = Vec::with_capacity(size);
+        let mut uninit_vec: Vec<MaybeUninit<u8>> = Vec::with_capacity(size + MAX_ALIGN - 1);
         unsafe {
-            uninit_vec.set_len(size);
+            uninit_vec.set_len(size + MAX_ALIGN - 1);
         }
 
         // Convert to initialized (we promise to only read written parts)
@@ -94,65 +194,122 @@ impl Page {
             std::mem::transmute::<Vec<MaybeUninit<u8>>, Vec<u8>>(uninit_vec)
         }
         .into_boxed_slice();
+        let start = data.as_ptr().align_offset(MAX_ALIGN);
 
         Self {
             id,
             data,
+            start,
+            capacity: size,
             used: AtomicUsize::new(0),
+            padding: AtomicUsize::new(0),
             generation,
             entries: parking_lot::RwLock::new(Vec::new()),
             empty_since: AtomicUsize::new(0),
+            pins: AtomicUsize::new(0),
+            purge_pending: AtomicBool::new(false),
         }
     }
 
-    /// Try to append data to this page (lock-free via CAS loop).
+    /// Try to append data to this page (lock-free via CAS loop). The
+    /// slices of `data` are stored back to back as one entry, starting at
+    /// a multiple of `align` (a power of two up to `MAX_ALIGN`). Returns
+    /// the entry's offset and size, and the padding skipped before it.
     ///
     /// Uses compare_exchange instead of fetch_add + rollback to prevent a race
     /// where concurrent overflow rollbacks corrupt the `used` counter.
     ///
-    /// Time: O(d) where d = data.len() (memcpy). The CAS loop is O(1) amortised
-    /// under low contention; under high contention it spins proportional to
-    /// the number of concurrent writers.
-    pub fn try_append(&self, data: &[u8]) -> Result<(u32, u32)> {
-        let data_len = data.len();
-
-        // Check if data fits in a page at all
-        if data_len > self.data.len() {
+    /// Time: O(d) where d = total length of `data` (memcpy). The CAS loop is
+    /// O(1) amortised under low contention; under high contention it spins
+    /// proportional to the number of concurrent writers.
+    pub fn try_append(
+        &self,
+        data: &[IoSlice<'_>],
+        expires_at: u64,
+        align: usize,
+    ) -> Result<(u32, u32, u32)> {
+        let data_len = gathered_len(data);
+        let (offset, padding) = self.claim(data_len, align)?;
+
+        // We own [offset, offset + data_len). Copy data.
+        unsafe {
+            copy_gathered(data, 0, self.base().add(offset), data_len);
+        }
+        self.publish_entry(offset, data_len, expires_at);
+
+        Ok((offset as u32, data_len as u32, padding as u32))
+    }
+
+    /// Reserve `len` zeroed bytes, at a multiple of `align`, for a caller
+    /// to fill in place. The entry is pending: it pins the page but cannot
+    /// be read until [`commit_entry`](Self::commit_entry). Returns the same
+    /// as `try_append`.
+    ///
+    /// Time: O(len) (memset), CAS loop as for `try_append`.
+    pub fn try_reserve(&self, len: usize, align: usize) -> Result<(u32, u32, u32)> {
+        self.reserve(len, align, true)
+    }
+
+    /// [`try_reserve`](Self::try_reserve) without zeroing, for a caller
+    /// that writes every byte it commits before anyone can read it, and
+    /// shrinks or deletes the entry to cover no more than that.
+    ///
+    /// Time: O(1), CAS loop as for `try_append`.
+    pub fn try_reserve_uninit(&self, len: usize, align: usize) -> Result<(u32, u32, u32)> {
+        self.reserve(len, align, false)
+    }
+
+    /// Shared body of `try_reserve` and `try_reserve_uninit`.
+    fn reserve(&self, len: usize, align: usize, zero: bool) -> Result<(u32, u32, u32)> {
+        let (offset, padding) = self.claim(len, align)?;
+
+        if zero {
+            // Hand out initialised memory, whatever the page held before
+            unsafe {
+                std::ptr::write_bytes(self.base().add(offset), 0, len);
+            }
+        }
+        self.publish_entry(offset, len, PENDING);
+
+        Ok((offset as u32, len as u32, padding as u32))
+    }
+
+    /// Claim `len` bytes at the end of the page, padded to start at a
+    /// multiple of `align`; returns their offset and the padding.
+    ///
+    /// Uses compare_exchange instead of fetch_add + rollback to prevent a race
+    /// where concurrent overflow rollbacks corrupt the `used` counter.
+    fn claim(&self, len: usize, align: usize) -> Result<(usize, usize)> {
+        // Check if data fits in a page at all. A fresh page is aligned for
+        // any `align`, so this also keeps `place` from looping.
+        if len > self.capacity {
             return Err(BlobError::DataTooLarge {
-                size: data_len,
-                max: self.data.len(),
+                size: len,
+                max: self.capacity,
             });
         }
 
-        // CAS loop: atomically claim [current_used, current_used + data_len)
+        // CAS loop: atomically claim [current_used, offset + len)
         loop {
             let current_used = self.used.load(Ordering::Acquire);
+            let offset = current_used.next_multiple_of(align);
 
-            if current_used + data_len > self.data.len() {
+            if offset + len > self.capacity {
                 return Err(BlobError::PageFull);
             }
 
             match self.used.compare_exchange_weak(
                 current_used,
-                current_used + data_len,
+                offset + len,
                 Ordering::AcqRel,
                 Ordering::Acquire,
             ) {
-                Ok(offset) => {
-                    // We own [offset, offset + data_len). Copy data.
-                    unsafe {
-                        let ptr = self.data.as_ptr() as *mut u8;
-                        std::ptr::copy_nonoverlapping(data.as_ptr(), ptr.add(offset), data_len);
+                Ok(_) => {
+                    let padding = offset - current_used;
+                    if padding > 0 {
+                        self.padding.fetch_add(padding, Ordering::Relaxed);
                     }
-
-                    // Add entry metadata
-                    let entry = EntryMetadata::new(offset as u32, data_len as u32);
-                    self.entries.write().push(entry);
-
-                    // Clear empty timestamp since we just added data
-                    self.empty_since.store(0, Ordering::Release);
-
-                    return Ok((offset as u32, data_len as u32));
+                    return Ok((offset, padding));
                 }
                 Err(_) => {
                     std::hint::spin_loop();
@@ -162,6 +319,52 @@ impl Page {
         }
     }
 
+    /// Record a whole-blob entry for claimed space.
+    fn publish_entry(&self, offset: usize, len: usize, expires_at: u64) {
+        // Add entry metadata
+        self.insert_entry(EntryMetadata::new(
+            offset as u32,
+            len as u32,
+            expires_at,
+            EntryKind::Whole,
+        ));
+
+        // Clear empty timestamp since we just added data
+        self.empty_since.store(0, Ordering::Release);
+    }
+
+    /// Pointer to the start of the entry at `offset`, for filling a
+    /// reserved entry in place. Valid while the page is alive.
+    pub fn entry_ptr(&self, offset: u32) -> *mut u8 {
+        debug_assert!(offset as usize <= self.capacity);
+        unsafe { self.base().add(offset as usize) }
+    }
+
+    /// Pointer to the start of the page.
+    fn base(&self) -> *mut u8 {
+        unsafe { (self.data.as_ptr() as *mut u8).add(self.start) }
+    }
+
+    /// Publish the pending entry at `offset` with its own expiry, as `size`
+    /// bytes holding `kind` of a blob (at most what was reserved). Fails
+    /// (returns `false`) if there is no pending entry there.
+    ///
+    /// Time: O(log e).
+    pub fn commit_entry(&self, offset: u32, expires_at: u64, size: u32, kind: EntryKind) -> bool {
+        let mut entries = self.entries.write();
+        let Ok(index) = entries.binary_search_by_key(&offset, |e| e.offset) else {
+            return false;
+        };
+        let entry = &mut entries[index];
+        if entry.expires_at.load(Ordering::Acquire) != PENDING || size > entry.size {
+            return false;
+        }
+        entry.size = size;
+        entry.kind = kind;
+        entry.expires_at.store(expires_at, Ordering::Release);
+        true
+    }
+
     /// Get a reference to data at the given offset.
     ///
     /// Time: O(1) — bounds check + slice creation.
@@ -169,33 +372,36 @@ impl Page {
         let start = offset as usize;
         let end = start + size as usize;
 
-        if end <= self.data.len() {
-            Some(&self.data[start..end])
+        if end <= self.capacity {
+            Some(&self.data[self.start + start..self.start + end])
         } else {
             None
         }
     }
 
-    /// Get available space in this page
-    pub fn available_space(&self) -> usize {
-        let used = self.used.load(Ordering::Acquire);
-        self.data.len().saturating_sub(used)
-    }
-
-    /// Try to append as much data as possible (CAS-loop variant).
+    /// Try to append as much data as possible (CAS-loop variant), from
+    /// `skip` bytes into the concatenation of `data`.
     /// Returns (offset, bytes_written) on success.
     ///
-    /// Time: O(min(d, available)) where d = data.len(). Same CAS amortisation as `try_append`.
-    pub fn try_append_partial(&self, data: &[u8]) -> Result<(u32, u32)> {
+    /// Time: O(min(d, available)) where d = bytes left in `data`. Same CAS
+    /// amortisation as `try_append`.
+    pub fn try_append_partial(
+        &self,
+        data: &[IoSlice<'_>],
+        skip: usize,
+        expires_at: u64,
+        kind: EntryKind,
+    ) -> Result<(u32, u32)> {
+        let remaining = gathered_len(data).saturating_sub(skip);
         loop {
             let current_used = self.used.load(Ordering::Acquire);
-            let available = self.data.len().saturating_sub(current_used);
+            let available = self.capacity.saturating_sub(current_used);
 
             if available == 0 {
                 return Err(BlobError::PageFull);
             }
 
-            let to_write = data.len().min(available);
+            let to_write = remaining.min(available);
 
             match self.used.compare_exchange_weak(
                 current_used,
@@ -205,12 +411,15 @@ impl Page {
             ) {
                 Ok(offset) => {
                     unsafe {
-                        let ptr = self.data.as_ptr() as *mut u8;
-                        std::ptr::copy_nonoverlapping(data.as_ptr(), ptr.add(offset), to_write);
+                        copy_gathered(data, skip, self.base().add(offset), to_write);
                     }
 
-                    let entry = EntryMetadata::new(offset as u32, to_write as u32);
-                    self.entries.write().push(entry);
+                    self.insert_entry(EntryMetadata::new(
+                        offset as u32,
+                        to_write as u32,
+                        expires_at,
+                        kind,
+                    ));
 
                     self.empty_since.store(0, Ordering::Release);
 
@@ -227,8 +436,18 @@ impl Page {
     /// Get current usage as a fraction (0.0 - 1.0)
     pub fn usage(&self) -> f32 {
         let used = self.used.load(Ordering::Acquire);
-        let capacity = self.data.len();
-        used as f32 / capacity as f32
+        used as f32 / self.capacity as f32
+    }
+
+    /// Bytes claimed so far, padding included
+    pub fn used_bytes(&self) -> usize {
+        self.used.load(Ordering::Acquire)
+    }
+
+    /// Bytes skipped to align payloads; part of `used_bytes` that holds no
+    /// data
+    pub fn padding_bytes(&self) -> usize {
+        self.padding.load(Ordering::Acquire)
     }
 
     /// Check if all entries are acknowledged or expired.
@@ -262,9 +481,10 @@ impl Page {
         }
     }
 
-    /// Check if this page should be freed (empty for longer than decay timeout)
+    /// Check if this page should be freed (empty for longer than decay
+    /// timeout, and not pinned)
     pub fn should_decay(&self, decay_timeout_ms: u64, ttl_ms: u64) -> bool {
-        if !self.is_empty(ttl_ms) {
+        if self.pins.load(Ordering::Acquire) > 0 || !self.is_empty(ttl_ms) {
             return false;
         }
 
@@ -280,16 +500,203 @@ impl Page {
 
     /// Acknowledge an entry at the given offset.
     ///
-    /// Time: O(e) — linear scan of entries to find the matching offset.
+    /// Time: O(log e) — binary search of the offset-sorted entries.
     pub fn acknowledge_entry(&self, offset: u32) -> bool {
-        let entries = self.entries.read();
+        self.with_entry(offset, EntryMetadata::acknowledge)
+            .is_some()
+    }
 
-        if let Some(entry) = entries.iter().find(|e| e.offset == offset) {
-            entry.acknowledge();
-            true
-        } else {
-            false
+    /// Take a reference to the entry at `offset`. Fails (returns `false`) if
+    /// there is no entry there, it has expired or its count reached zero.
+    ///
+    /// Time: O(log e).
+    pub fn retain_entry(&self, offset: u32, ttl_ms: u64) -> bool {
+        self.with_entry(offset, |entry| entry.retain(ttl_ms))
+            .unwrap_or(false)
+    }
+
+    /// Drop a reference to the entry at `offset`. Fails (returns `false`)
+    /// if there is no entry there or its count is already zero.
+    ///
+    /// Time: O(log e).
+    pub fn release_entry(&self, offset: u32) -> bool {
+        self.with_entry(offset, EntryMetadata::release)
+            .unwrap_or(false)
+    }
+
+    /// Push the expiry of the entry at `offset` out to at least
+    /// `expires_at`. Fails (returns `false`) if there is no entry there or it
+    /// has already expired; never shortens the entry's lifetime.
+    ///
+    /// Time: O(log e).
+    pub fn touch_entry(&self, offset: u32, expires_at: u64, ttl_ms: u64) -> bool {
+        self.with_entry(offset, |entry| {
+            !entry.is_expired(ttl_ms)
+                && entry
+                    .expires_at
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                        match current {
+                            // A concurrent delete wins; pending entries
+                            // get their expiry on commit
+                            DELETED | PENDING => None,
+                            // An entry on the default TTL keeps that expiry as a floor
+                            0 => Some(expires_at.max(entry.timestamp.saturating_add(ttl_ms))),
+                            _ => Some(expires_at.max(current)),
+                        }
+                    })
+                    .is_ok()
+        })
+        .unwrap_or(false)
+    }
+
+    /// Delete the entry at `offset`: it reads as expired from now on and
+    /// counts as done, whatever references were left. With `zero`, its
+    /// bytes are overwritten with zeros as well, once no `view` of the
+    /// page is left. Fails (returns `false`) if there is no entry there.
+    ///
+    /// Time: O(log e), plus O(size) to zero.
+    pub fn delete_entry(&self, offset: u32, zero: bool) -> bool {
+        let found = self
+            .with_entry(offset, |entry| {
+                entry.delete();
+                if zero {
+                    entry.zero_pending.store(true, Ordering::Release);
+                    self.purge_pending.store(true, Ordering::Release);
+                }
+            })
+            .is_some();
+        if found && zero {
+            // Pairs with the fence in `pin`: either the pin sees the
+            // delete, or this sees the pin and leaves zeroing to `unpin`
+            fence(Ordering::SeqCst);
+            if self.pins.load(Ordering::Relaxed) == 0 {
+                self.zero_purged();
+            }
+        }
+        found
+    }
+
+    /// Pin the page for a reference into its data: it is not freed, and
+    /// purged bytes are not zeroed, until the matching [`unpin`](Self::unpin).
+    /// Check that the referenced entry is still readable after pinning.
+    ///
+    /// Time: O(1).
+    pub fn pin(&self) {
+        self.pins.fetch_add(1, Ordering::AcqRel);
+        fence(Ordering::SeqCst);
+    }
+
+    /// Drop a pin. The last one zeroes entries purged meanwhile.
+    ///
+    /// Time: O(1), or O(e + purged bytes) for the last pin after a purge.
+    pub fn unpin(&self) {
+        if self.pins.fetch_sub(1, Ordering::AcqRel) == 1 {
+            fence(Ordering::SeqCst);
+            self.zero_purged();
+        }
+    }
+
+    /// Zero the bytes of entries whose purge was left for later.
+    fn zero_purged(&self) {
+        if !self.purge_pending.swap(false, Ordering::AcqRel) {
+            return;
         }
+        for entry in self.entries.read().iter() {
+            if entry.zero_pending.swap(false, Ordering::AcqRel) {
+                // SAFETY: the range was written by the entry's append and
+                // lies within `data`. Readers that raced the delete discard
+                // what they copied, see `PinnedBlobStore::get`, and views
+                // of it are gone.
+                unsafe {
+                    std::ptr::write_bytes(
+                        self.base().add(entry.offset as usize),
+                        0,
+                        entry.size as usize,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Dead letters for the blobs starting in this page that still hold a
+    /// reference, i.e. expired without being acknowledged. Meant for a page
+    /// that is about to be freed; pieces of multi-page blobs are reported
+    /// once, through their first piece.
+    ///
+    /// Time: O(e).
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        let now = now_ms();
+        self.entries
+            .read()
+            .iter()
+            .filter(|e| e.refs.load(Ordering::Acquire) > 0)
+            .filter_map(|e| {
+                let mut handle = match e.kind {
+                    EntryKind::Whole => BlobHandle::new(self.id, e.offset, e.size, self.generation),
+                    EntryKind::Head {
+                        end_page_id,
+                        total_size,
+                    } => BlobHandle::new_multi_page(
+                        self.id,
+                        e.offset,
+                        end_page_id,
+                        total_size,
+                        self.generation,
+                    ),
+                    EntryKind::Continuation => return None,
+                };
+                handle.timestamp = e.timestamp;
+                Some(DeadLetter {
+                    handle: DeadLetterHandle::Heap(handle),
+                    age_ms: now.saturating_sub(e.timestamp),
+                    size: handle.total_size,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the entry at `offset` cannot be read: it has expired, was
+    /// deleted, or is reserved but not committed. `None` if there is no
+    /// entry there.
+    ///
+    /// Time: O(log e).
+    pub fn entry_unreadable(&self, offset: u32, ttl_ms: u64) -> Option<bool> {
+        self.with_entry(offset, |entry| {
+            entry.is_pending() || entry.is_expired(ttl_ms)
+        })
+    }
+
+    /// The entry covering `offset`: where it starts, and how many bytes of
+    /// its blob follow from there (all of them, for the first piece of a
+    /// multi-page blob). `None` if no entry covers it.
+    ///
+    /// Time: O(log e).
+    pub fn entry_containing(&self, offset: u32) -> Option<(u32, u64)> {
+        let entries = self.entries.read();
+        let index = entries
+            .partition_point(|e| e.offset <= offset)
+            .checked_sub(1)?;
+        let entry = &entries[index];
+        let extent = match entry.kind {
+            EntryKind::Head { total_size, .. } => total_size,
+            EntryKind::Whole | EntryKind::Continuation => entry.size as u64,
+        };
+        (((offset - entry.offset) as u64) < extent).then_some((entry.offset, extent))
+    }
+
+    /// Run `f` on the entry starting at `offset`, if any.
+    fn with_entry<R>(&self, offset: u32, f: impl FnOnce(&EntryMetadata) -> R) -> Option<R> {
+        let entries = self.entries.read();
+        let index = entries.binary_search_by_key(&offset, |e| e.offset).ok()?;
+        Some(f(&entries[index]))
+    }
+
+    /// Record a new entry, keeping `entries` sorted by offset. Concurrent
+    /// appends may finish out of order, but each lands near the end.
+    fn insert_entry(&self, entry: EntryMetadata) {
+        let mut entries = self.entries.write();
+        let index = entries.partition_point(|e| e.offset < entry.offset);
+        entries.insert(index, entry);
     }
 }
 
@@ -297,8 +704,9 @@ impl std::fmt::Debug for Page {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Page")
             .field("id", &self.id)
-            .field("capacity", &self.data.len())
+            .field("capacity", &self.capacity)
             .field("used", &self.used.load(Ordering::Acquire))
+            .field("padding", &self.padding.load(Ordering::Acquire))
             .field("generation", &self.generation)
             .field("entry_count", &self.entries.read().len())
             .finish()
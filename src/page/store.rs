@@ -1,15 +1,25 @@
+use crossbeam_channel::{Receiver, Sender};
 use parking_lot::Mutex;
 use parking_lot::RwLock;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
+use std::io::{IoSlice, SeekFrom};
+use std::ops::RangeBounds;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use crate::backend::segmented::SegmentedBackend;
-use crate::backend::shared::SharedBackend;
+use crate::backend::shared::{
+    SharedBackend, SharedBlobReader, SharedBlobWriter, SharedValueRef, SharedWriteSlot,
+};
 use crate::backend::StorageBackend;
+use crate::page::page::{EntryKind, Page};
 use crate::profiling::Profiler;
-use crate::types::{BlobError, BlobHandle, Config, OverflowHandle, Result};
+use crate::types::{
+    check_align, gathered_len, now_ms, pod_bytes, pod_bytes_mut, pod_zeroed, seek_position,
+    BlobError, BlobHandle, CompactHandle, Config, DeadLetter, Handle, OverflowHandle, Pod, Result,
+    SharedConfig, DEAD_LETTER_CAPACITY,
+};
 
 /// The main blob store providing pointer-stable storage.
 ///
@@ -40,11 +50,18 @@ pub struct PinnedBlobStore {
 
     /// Optional shared-memory backend (present when mode = Shared)
     shared: Option<SharedBackend>,
+
+    /// Shared entries whose claim lapsed, queued for redelivery
+    redelivery: (Sender<OverflowHandle>, Receiver<OverflowHandle>),
+
+    /// Entries freed before they were acknowledged, until taken
+    dead_letters: (Sender<DeadLetter>, Receiver<DeadLetter>),
 }
 
 impl PinnedBlobStore {
     /// Create a new blob store with the given configuration
     pub fn new(config: Config) -> Result<Self> {
+        config.validate()?;
         // Use Segmented backend (heap-allocated pages with MaybeUninit optimization)
         let backend: Box<dyn StorageBackend> = Box::new(SegmentedBackend::new());
 
@@ -57,6 +74,8 @@ impl PinnedBlobStore {
             generation_counter: AtomicU32::new(0),
             profiler: Profiler::new(),
             shared: None,
+            redelivery: crossbeam_channel::unbounded(),
+            dead_letters: crossbeam_channel::bounded(DEAD_LETTER_CAPACITY),
         };
 
         // Allocate the first page
@@ -91,7 +110,27 @@ impl PinnedBlobStore {
         chunk_size: usize,
         max_chunks: Option<u32>,
     ) -> Result<Self> {
-        let shared = SharedBackend::create(namespace, chunk_size, max_chunks)?;
+        let shared_config = SharedConfig {
+            max_chunks,
+            ..SharedConfig::from(&config)
+        };
+        Self::new_shared_with_config(config, namespace, chunk_size, shared_config)
+    }
+
+    /// Create a blob store backed by shared memory with an explicit shared policy.
+    ///
+    /// `shared_config` is stored in the namespace's control file; processes
+    /// that `attach_shared` later use it instead of their own `Config` for
+    /// TTL, decay timeout, chunk limits and prefetch.
+    #[cfg(unix)]
+    pub fn new_shared_with_config(
+        config: Config,
+        namespace: &str,
+        chunk_size: usize,
+        shared_config: SharedConfig,
+    ) -> Result<Self> {
+        config.validate()?;
+        let shared = SharedBackend::create_with_config(namespace, chunk_size, &shared_config)?;
         let backend: Box<dyn StorageBackend> = Box::new(SegmentedBackend::new());
         Ok(Self {
             backend: Arc::new(RwLock::new(backend)),
@@ -102,13 +141,19 @@ impl PinnedBlobStore {
             generation_counter: AtomicU32::new(0),
             profiler: Profiler::new(),
             shared: Some(shared),
+            redelivery: crossbeam_channel::unbounded(),
+            dead_letters: crossbeam_channel::bounded(DEAD_LETTER_CAPACITY),
         })
     }
 
     /// Attach to an existing shared-memory blob store (non-creator process).
+    ///
+    /// Shared-mode TTL, decay timeout, limits and prefetch come from the
+    /// namespace's control file; `config` only governs the heap side.
     #[cfg(unix)]
     pub fn attach_shared(config: Config, namespace: &str) -> Result<Self> {
-        let shared = SharedBackend::attach(namespace, None)?;
+        config.validate()?;
+        let shared = SharedBackend::attach(namespace)?;
         let backend: Box<dyn StorageBackend> = Box::new(SegmentedBackend::new());
         Ok(Self {
             backend: Arc::new(RwLock::new(backend)),
@@ -119,6 +164,8 @@ impl PinnedBlobStore {
             generation_counter: AtomicU32::new(0),
             profiler: Profiler::new(),
             shared: Some(shared),
+            redelivery: crossbeam_channel::unbounded(),
+            dead_letters: crossbeam_channel::bounded(DEAD_LETTER_CAPACITY),
         })
     }
 
@@ -164,7 +211,229 @@ impl PinnedBlobStore {
     ///
     /// For data larger than page size, automatically spans multiple pages.
     pub fn append(&self, data: &[u8]) -> Result<BlobHandle> {
-        if data.is_empty() {
+        self.append_entry(&[IoSlice::new(data)], 0, self.config.align)
+    }
+
+    /// Append data starting at an address that is a multiple of `align`,
+    /// for consumers such as SIMD decoders or `O_DIRECT` I/O that need
+    /// aligned buffers. `align` must be a power of two up to [`MAX_ALIGN`];
+    /// `Config::align` sets the alignment of the other appends.
+    ///
+    /// The bytes skipped to get there stay unused until the page is freed,
+    /// and count as free space in the profiler's fragmentation stats.
+    /// Blobs larger than a page start a fresh page, which is aligned to
+    /// `MAX_ALIGN` anyway.
+    ///
+    /// [`MAX_ALIGN`]: crate::types::MAX_ALIGN
+    pub fn append_aligned(&self, data: &[u8], align: usize) -> Result<BlobHandle> {
+        check_align(align)?;
+        self.append_entry(&[IoSlice::new(data)], 0, align)
+    }
+
+    /// Append the concatenation of `bufs` as a single entry, without
+    /// joining them first: the space is reserved once and each slice is
+    /// copied into place. The handle is the same as for one `append` of
+    /// the joined bytes.
+    pub fn append_vectored(&self, bufs: &[IoSlice<'_>]) -> Result<BlobHandle> {
+        self.append_entry(bufs, 0, self.config.align)
+    }
+
+    /// Append one `T` as its raw bytes, aligned for `T` (or to
+    /// `Config::align`, if larger), so [`view`](Self::view) can borrow it
+    /// in place. Returns `InvalidAlignment` if `T` needs more than
+    /// [`MAX_ALIGN`](crate::types::MAX_ALIGN).
+    pub fn append_value<T: Pod>(&self, value: &T) -> Result<Handle<T>> {
+        let align = align_of::<T>().max(self.config.align);
+        check_align(align)?;
+        self.append_entry(&[IoSlice::new(pod_bytes(value))], 0, align)
+            .map(Handle::new)
+    }
+
+    /// Reserve `len` bytes in a page for a producer that writes its payload
+    /// in place, such as a serializer, instead of into a temporary buffer.
+    /// Fill the returned slot, then [`commit`](WriteSlot::commit) it to get
+    /// the handle. `get` cannot see the entry before that, and a slot
+    /// dropped without committing is deleted.
+    ///
+    /// The slot pins its page but holds no lock, so other appends and
+    /// cleanup go on meanwhile. Returns `DataTooLarge` if `len` is 0 or
+    /// more than a page.
+    pub fn reserve(&self, len: usize) -> Result<WriteSlot<'_>> {
+        if len == 0 || len > self.config.page_size {
+            return Err(BlobError::DataTooLarge {
+                size: len,
+                max: self.config.page_size,
+            });
+        }
+        let handle = self.place(|page| page.try_reserve(len, self.config.align))?;
+        // The pending entry keeps the page from being freed
+        let ptr = self
+            .backend
+            .read()
+            .get_page(handle.page_id)
+            .ok_or(BlobError::InvalidHandle)?
+            .entry_ptr(handle.offset);
+        Ok(WriteSlot {
+            store: self,
+            handle,
+            ptr,
+            committed: false,
+        })
+    }
+
+    /// Start a blob of unknown length, written through `std::io::Write`
+    /// as its bytes arrive; [`finish`](BlobWriter::finish) returns one
+    /// handle for all of it. A writer dropped unfinished releases what it
+    /// wrote for reclamation.
+    ///
+    /// The writer fills fresh pages of its own, and `finish` publishes
+    /// them in place as a multi-page blob. If concurrent appends took page
+    /// ids in between, the pages are renumbered to consecutive ids first;
+    /// their bytes do not move. Only a blob that fits in one page is
+    /// copied, like `append`, so it does not hold a whole page.
+    pub fn writer(&self) -> BlobWriter<'_> {
+        BlobWriter {
+            store: self,
+            pages: Vec::new(),
+            len: 0,
+            finished: false,
+        }
+    }
+
+    /// Allocate a page for a `BlobWriter` at the high water mark, so
+    /// consecutive calls tend to return consecutive ids, and reserve all
+    /// of it. Returns its id and a pointer to its data.
+    ///
+    /// The page is not zeroed: the writer commits only bytes it wrote.
+    fn allocate_writer_page(&self) -> Result<(u32, *mut u8)> {
+        let current_hwm = self.high_water_mark.load(Ordering::Acquire);
+        if current_hwm >= u32::MAX - 1 {
+            return Err(BlobError::OutOfMemory);
+        }
+        let page_id = self.high_water_mark.fetch_add(1, Ordering::AcqRel) + 1;
+        self.allocate_page(page_id)?;
+        let backend = self.backend.read();
+        // A sweep may free the page before it holds an entry
+        let page = backend.get_page(page_id).ok_or(BlobError::PageFull)?;
+        page.try_reserve_uninit(self.config.page_size, 1)?;
+        Ok((page_id, page.entry_ptr(0)))
+    }
+
+    /// Publish what a `BlobWriter` wrote to `pages`, `len` bytes in all.
+    /// Returns the handle and whether the pages were published in place;
+    /// if not, the bytes were copied and the pages are still pending.
+    ///
+    /// Pages that did not end up contiguous are renumbered first, so only
+    /// a blob that fits in one page is ever copied.
+    fn finish_writer(&self, pages: &mut [(u32, *mut u8)], len: u64) -> Result<(BlobHandle, bool)> {
+        let page_size = self.config.page_size;
+        if pages.len() < 2 {
+            // SAFETY: the pending entry keeps the page alive, and holds
+            // `len` written bytes.
+            let data = unsafe { std::slice::from_raw_parts(pages[0].1, len as usize) };
+            return self.append(data).map(|handle| (handle, false));
+        }
+        let contiguous = pages
+            .iter()
+            .enumerate()
+            .all(|(i, &(id, _))| id == pages[0].0 + i as u32);
+        if !contiguous {
+            self.renumber_writer_pages(pages)?;
+        }
+
+        let start_page_id = pages[0].0;
+        let end_page_id = start_page_id + pages.len() as u32 - 1;
+        let expires_at = now_ms().saturating_add(self.config.default_ttl_ms);
+        let backend = self.backend.read();
+        let mut generation = 0;
+        for (i, &(page_id, _)) in pages.iter().enumerate() {
+            let page = backend.get_page(page_id).ok_or(BlobError::InvalidHandle)?;
+            let size = (len - (i * page_size) as u64).min(page_size as u64) as u32;
+            let kind = if i == 0 {
+                generation = page.generation;
+                EntryKind::Head {
+                    end_page_id,
+                    total_size: len,
+                }
+            } else {
+                EntryKind::Continuation
+            };
+            if !page.commit_entry(0, expires_at, size, kind) {
+                return Err(BlobError::InvalidHandle);
+            }
+        }
+
+        self.profiler.record_append(len as usize);
+        self.profiler.record_multi_page_span();
+        Ok((
+            BlobHandle::new_multi_page(start_page_id, 0, end_page_id, len, generation),
+            true,
+        ))
+    }
+
+    /// Move a `BlobWriter`'s pages to consecutive fresh ids, for when
+    /// concurrent allocations took ids in between. Only the page structs
+    /// move; their data stays where the writer wrote it. The ids given up
+    /// are recycled like those of freed pages.
+    fn renumber_writer_pages(&self, pages: &mut [(u32, *mut u8)]) -> Result<()> {
+        let num_pages = pages.len() as u32;
+        let current_hwm = self.high_water_mark.load(Ordering::Acquire);
+        if current_hwm as u64 + num_pages as u64 >= u32::MAX as u64 {
+            return Err(BlobError::OutOfMemory);
+        }
+        let start_page_id = self.high_water_mark.fetch_add(num_pages, Ordering::AcqRel) + 1;
+
+        let mut backend = self.backend.write();
+        for (i, (page_id, _)) in pages.iter_mut().enumerate() {
+            let to = start_page_id + i as u32;
+            if !backend.rename_page(*page_id, to) {
+                return Err(BlobError::InvalidHandle);
+            }
+            self.free_pages.lock().push(Reverse(*page_id));
+            *page_id = to;
+        }
+        Ok(())
+    }
+
+    /// Publish a reserved entry, with the default TTL counted from now.
+    fn commit_reserved(&self, handle: &BlobHandle) -> Result<BlobHandle> {
+        let expires_at = now_ms().saturating_add(self.config.default_ttl_ms);
+        let backend = self.backend.read();
+        let page = backend
+            .get_page(handle.page_id)
+            .ok_or(BlobError::InvalidHandle)?;
+        if !page.commit_entry(handle.offset, expires_at, handle.size, EntryKind::Whole) {
+            return Err(BlobError::InvalidHandle);
+        }
+        self.profiler.record_append(handle.size as usize);
+        Ok(BlobHandle::new(
+            handle.page_id,
+            handle.offset,
+            handle.size,
+            handle.generation,
+        ))
+    }
+
+    /// Append data that expires `ttl_ms` after now instead of after
+    /// `Config::default_ttl_ms`. `get` and `cleanup_acknowledged` honour
+    /// the entry's own expiry, which may be shorter or longer than the default.
+    pub fn append_with_ttl(&self, data: &[u8], ttl_ms: u64) -> Result<BlobHandle> {
+        // Never 0, which would mean "default TTL"
+        let expires_at = now_ms().saturating_add(ttl_ms).max(1);
+        self.append_entry(&[IoSlice::new(data)], expires_at, self.config.align)
+    }
+
+    /// Shared body of the append variants. `data` is stored as if its
+    /// slices were one buffer, at a multiple of `align`; `expires_at` is
+    /// the entry's own expiry (ms since epoch), or 0 for the default TTL.
+    fn append_entry(
+        &self,
+        data: &[IoSlice<'_>],
+        expires_at: u64,
+        align: usize,
+    ) -> Result<BlobHandle> {
+        let len = gathered_len(data);
+        if len == 0 {
             return Err(BlobError::DataTooLarge {
                 size: 0,
                 max: self.config.page_size,
@@ -172,26 +441,38 @@ impl PinnedBlobStore {
         }
 
         // For data larger than page size, split across multiple pages
-        if data.len() > self.config.page_size {
-            return self.append_multi_page(data);
+        if len > self.config.page_size {
+            return self.append_multi_page(data, len, expires_at);
         }
 
         // Fast path: data fits in a single page
+        let handle = self.place(|page| page.try_append(data, expires_at, align))?;
+
+        // Record append operation
+        self.profiler.record_append(len);
+        Ok(handle)
+    }
+
+    /// Run `write` on the current page, moving on to the next free page
+    /// while it reports `PageFull`, and return a handle to what it wrote.
+    /// `write` returns the entry's offset, size and leading padding.
+    fn place(&self, write: impl Fn(&Page) -> Result<(u32, u32, u32)>) -> Result<BlobHandle> {
         loop {
             let current_page_id = self.current_page.load(Ordering::Acquire);
 
             // Try to append to current page
             let backend = self.backend.read();
             if let Some(page) = backend.get_page(current_page_id) {
-                match page.try_append(data) {
-                    Ok((offset, size)) => {
+                match write(page) {
+                    Ok((offset, size, padding)) => {
+                        if padding > 0 {
+                            self.profiler.record_padding(padding as usize);
+                        }
+
                         // Success! Create handle
                         let handle =
                             BlobHandle::new(current_page_id, offset, size, page.generation);
 
-                        // Record append operation
-                        self.profiler.record_append(data.len());
-
                         // Prefetch Check: If full, perform proactive allocation
                         // Currently, for recycled implementation, proactive prefetch is tricky because "Next" isn't strictly +1.
                         // We skip prefetch for now to ensure simple recycling logic (Lazy Allocation).
@@ -236,13 +517,18 @@ impl PinnedBlobStore {
     }
 
     /// Append large data spanning multiple pages
-    fn append_multi_page(&self, data: &[u8]) -> Result<BlobHandle> {
+    fn append_multi_page(
+        &self,
+        data: &[IoSlice<'_>],
+        len: usize,
+        expires_at: u64,
+    ) -> Result<BlobHandle> {
         // Multi-page strategy:
         // We CANNOT easily span across random recycled fragments (Swiss Cheese).
         // Solution: Always allocate a fresh CONTIGUOUS block at the High Water Mark.
 
         let chunk_size = self.config.page_size;
-        let num_pages = data.len().div_ceil(chunk_size);
+        let num_pages = len.div_ceil(chunk_size);
 
         // Check for wraparound before reserving contiguous IDs
         let current_hwm = self.high_water_mark.load(Ordering::Acquire);
@@ -264,7 +550,7 @@ impl PinnedBlobStore {
         }
 
         // Write data
-        let mut remaining = data;
+        let mut written_total = 0;
         let mut start_offset = None;
         let mut first_generation = 0;
 
@@ -276,26 +562,35 @@ impl PinnedBlobStore {
                 first_generation = page.generation;
             }
 
-            let (offset, written) = page.try_append_partial(remaining)?;
+            let kind = if i == 0 {
+                EntryKind::Head {
+                    end_page_id,
+                    total_size: len as u64,
+                }
+            } else {
+                EntryKind::Continuation
+            };
+            let (offset, written) =
+                page.try_append_partial(data, written_total, expires_at, kind)?;
 
             if i == 0 {
                 start_offset = Some(offset);
             }
 
-            remaining = &remaining[written as usize..];
+            written_total += written as usize;
             // We just allocated these fresh, so they should accept data.
             // If they are somehow full (impossible), we error out.
         }
 
         // Record metrics
-        self.profiler.record_append(data.len());
+        self.profiler.record_append(len);
         self.profiler.record_multi_page_span();
 
         Ok(BlobHandle::new_multi_page(
             start_page_id,
             start_offset.unwrap_or(0),
             end_page_id,
-            data.len() as u64,
+            len as u64,
             first_generation,
         ))
     }
@@ -305,11 +600,6 @@ impl PinnedBlobStore {
     ///
     /// Supports both single-page and multi-page data.
     pub fn get(&self, handle: &BlobHandle) -> Option<Vec<u8>> {
-        // Check TTL
-        if handle.is_expired(self.config.default_ttl_ms) {
-            return None;
-        }
-
         // Handle multi-page data
         if handle.is_multi_page() {
             return self.get_multi_page(handle);
@@ -317,18 +607,19 @@ impl PinnedBlobStore {
 
         // Single-page fast path
         let backend = self.backend.read();
-        let page = backend.get_page(handle.page_id)?;
 
-        // Validate generation
-        if page.generation != handle.generation {
-            return None;
-        }
+        // Validate generation and TTL (the entry's own, if it was appended
+        // with one)
+        let page = self.readable_head(&**backend, handle).ok()?;
 
         // Get data and return owned copy
         let result = page
             .get(handle.offset, handle.size)
             .map(|slice| slice.to_vec());
 
+        // A delete that raced the copy may have zeroed part of it
+        self.readable_head(&**backend, handle).ok()?;
+
         if result.is_some() {
             self.profiler.record_read(handle.size as usize);
         }
@@ -336,60 +627,331 @@ impl PinnedBlobStore {
         result
     }
 
-    /// Get multi-page data
-    fn get_multi_page(&self, handle: &BlobHandle) -> Option<Vec<u8>> {
-        let mut result = Vec::with_capacity(handle.total_size as usize);
-        let backend = self.backend.read();
-
-        for page_id in handle.page_id..=handle.end_page_id {
-            let page = backend.get_page(page_id)?;
-
-            if page_id == handle.page_id {
-                // First page: from start_offset to end
-                let available = page.available_space();
-                let page_capacity = self.config.page_size;
-                let used = page_capacity - available;
-                let to_read = used - handle.offset as usize;
+    /// Copy the bytes of the blob `handle` refers to, from `offset` on,
+    /// into `buf`, without copying the rest. Returns how many: less than
+    /// `buf.len()` only at the end of the blob, 0 at or past it.
+    ///
+    /// Errors: `InvalidHandle` if the page was freed or recycled,
+    /// `HandleExpired` if the entry was deleted or is past its TTL; the
+    /// cases in which [`get`](Self::get) returns `None`.
+    ///
+    /// Time: O(n) in the bytes copied.
+    pub fn read_at(&self, handle: &BlobHandle, offset: u64, buf: &mut [u8]) -> Result<usize> {
+        let mut read = 0;
+        loop {
+            let n = self.read_fragment(handle, offset + read as u64, &mut buf[read..])?;
+            if n == 0 {
+                break;
+            }
+            read += n;
+        }
+        if read > 0 {
+            self.profiler.record_read(read);
+        }
+        Ok(read)
+    }
 
-                if let Some(data) = page.get(handle.offset, to_read as u32) {
-                    result.extend_from_slice(data);
-                }
-            } else if page_id == handle.end_page_id {
-                // Last page: from 0 to whatever is needed
-                let remaining = handle.total_size as usize - result.len();
-                if let Some(data) = page.get(0, remaining as u32) {
-                    result.extend_from_slice(data);
+    /// Like [`get`](Self::get), but into `buf`, replacing its contents, so
+    /// one buffer can be reused across reads. Returns the blob's size.
+    ///
+    /// Errors: as for [`read_at`](Self::read_at). `buf` is left empty.
+    pub fn get_into(&self, handle: &BlobHandle, buf: &mut Vec<u8>) -> Result<usize> {
+        buf.clear();
+        // Checked first, so a stale handle allocates nothing
+        self.readable_head(&**self.backend.read(), handle)?;
+        buf.resize(handle.total_size as usize, 0);
+        match self.read_at(handle, 0, buf) {
+            Ok(n) if n == buf.len() => {
+                if handle.is_multi_page() {
+                    self.profiler.record_multi_page_span();
                 }
-            } else {
-                // Middle pages: all data
-                let available = page.available_space();
-                let page_capacity = self.config.page_size;
-                let used = page_capacity - available;
+                Ok(n)
+            }
+            Ok(_) => {
+                buf.clear();
+                Err(BlobError::InvalidHandle)
+            }
+            Err(e) => {
+                buf.clear();
+                Err(e)
+            }
+        }
+    }
+
+    /// Open a reader over the blob `handle` refers to, for streaming it
+    /// without materialising it: [`BlobReader`] implements `Read` and
+    /// `Seek`, and copies straight from the pages into the caller's buffer.
+    ///
+    /// Every read rechecks the handle as [`get`](Self::get) does, so once
+    /// the entry expires, is deleted or its page is recycled, reads fail
+    /// with an `io::Error` wrapping `HandleExpired` or `InvalidHandle`
+    /// instead of returning other data.
+    ///
+    /// Errors: as for those reads, if the blob is not readable now.
+    pub fn open_reader(&self, handle: &BlobHandle) -> Result<BlobReader<'_>> {
+        self.readable_head(&**self.backend.read(), handle)?;
+        Ok(BlobReader {
+            store: self,
+            handle: *handle,
+            pos: 0,
+        })
+    }
 
-                if let Some(data) = page.get(0, used as u32) {
-                    result.extend_from_slice(data);
+    /// Read the `T` that `handle` refers to, borrowing it straight from its
+    /// page when it is aligned for `T`; otherwise, e.g. for a slice typed
+    /// with `Handle::from_raw` or a value spanning pages, it is copied out.
+    ///
+    /// A borrowed view pins its page: the page is not freed, and a
+    /// [`purge`](Self::purge) does not zero its bytes, until the view is
+    /// dropped. The entry may still expire or be deleted meanwhile; the
+    /// view keeps reading the value it was created with.
+    ///
+    /// Errors: as for [`read_at`](Self::read_at).
+    ///
+    /// Time: O(1) when borrowed, O(size_of::<T>()) when copied.
+    pub fn view<T: Pod>(&self, handle: &Handle<T>) -> Result<ValueRef<'_, T>> {
+        let raw = handle.raw();
+        if !raw.is_multi_page() {
+            let backend = self.backend.read();
+            let page = self.readable_head(&**backend, raw)?;
+            let ptr = page
+                .get(raw.offset, raw.size)
+                .ok_or(BlobError::InvalidHandle)?
+                .as_ptr() as *const T;
+            if ptr.is_aligned() {
+                page.pin();
+                // A delete that raced the pin may zero the bytes before it
+                // sees the pin
+                if let Err(e) = self.readable_head(&**backend, raw) {
+                    page.unpin();
+                    return Err(e);
                 }
+                self.profiler.record_read(size_of::<T>());
+                return Ok(ValueRef {
+                    inner: Value::Borrowed {
+                        store: self,
+                        page_id: raw.page_id,
+                        ptr,
+                    },
+                });
             }
         }
+        let mut value = pod_zeroed::<T>();
+        if self.read_at(raw, 0, pod_bytes_mut(&mut value))? != size_of::<T>() {
+            return Err(BlobError::InvalidHandle);
+        }
+        Ok(ValueRef {
+            inner: Value::Owned(value),
+        })
+    }
+
+    /// Drop the pin a borrowed [`ValueRef`] holds on `page_id`.
+    fn unpin_page(&self, page_id: u32) {
+        // Pinned pages are never removed, so the id still names the page
+        if let Some(page) = self.backend.read().get_page(page_id) {
+            page.unpin();
+        }
+    }
 
-        // Record multi-page read
-        if !result.is_empty() {
-            self.profiler.record_read(result.len());
-            self.profiler.record_multi_page_span();
+    /// The page holding the entry `handle` refers to, if it can be read.
+    ///
+    /// Errors: `InvalidHandle` if the page was freed or recycled, or holds
+    /// no such entry, `HandleExpired` if the entry is pending, deleted or
+    /// past its TTL.
+    fn readable_head<'b>(
+        &self,
+        backend: &'b dyn StorageBackend,
+        handle: &BlobHandle,
+    ) -> Result<&'b Page> {
+        let page = backend
+            .get_page(handle.page_id)
+            .filter(|page| page.generation == handle.generation)
+            .ok_or(BlobError::InvalidHandle)?;
+        let (offset, _) = Self::locate(page, handle).ok_or(BlobError::InvalidHandle)?;
+        match page.entry_unreadable(offset, self.config.default_ttl_ms) {
+            Some(false) => Ok(page),
+            Some(true) => Err(BlobError::HandleExpired),
+            None => Err(BlobError::InvalidHandle),
         }
+    }
+
+    /// Copy bytes of the blob `handle` refers to, from `pos` on, into `dst`,
+    /// up to the end of the page they start in. Returns how many; 0 at or
+    /// past the end.
+    ///
+    /// Errors: as for [`readable_head`](Self::readable_head).
+    fn read_fragment(&self, handle: &BlobHandle, pos: u64, dst: &mut [u8]) -> Result<usize> {
+        let backend = self.backend.read();
+        let first = self.readable_head(&**backend, handle)?;
+        if pos >= handle.total_size || dst.is_empty() {
+            return Ok(0);
+        }
+
+        // Every page of a blob but the last is filled to the end, so its
+        // bytes are laid out as if its pages were one
+        let page_size = self.config.page_size as u64;
+        let at = handle.offset as u64 + pos;
+        let page = match at / page_size {
+            0 => first,
+            n => u32::try_from(handle.page_id as u64 + n)
+                .ok()
+                .filter(|&id| id <= handle.end_page_id)
+                .and_then(|id| backend.get_page(id))
+                .ok_or(BlobError::InvalidHandle)?,
+        };
+        let (at, room) = (at % page_size, page_size - at % page_size);
+        let n = (dst.len() as u64).min(room).min(handle.total_size - pos) as usize;
+        let src = page
+            .get(at as u32, n as u32)
+            .ok_or(BlobError::InvalidHandle)?;
+        dst[..n].copy_from_slice(src);
+
+        // A delete that raced the copy may have zeroed part of it
+        self.readable_head(&**backend, handle)?;
+        Ok(n)
+    }
 
+    /// Get multi-page data
+    fn get_multi_page(&self, handle: &BlobHandle) -> Option<Vec<u8>> {
+        let mut result = vec![0u8; handle.total_size as usize];
+        if self.read_at(handle, 0, &mut result).ok()? != result.len() {
+            return None;
+        }
+        self.profiler.record_multi_page_span();
         Some(result)
     }
 
+    /// Where the entry that `handle` refers to, in full or as a slice,
+    /// starts in its first page, and whether `handle` is a slice of it.
+    fn locate(page: &Page, handle: &BlobHandle) -> Option<(u32, bool)> {
+        let (offset, extent) = page.entry_containing(handle.offset)?;
+        let end = (handle.offset - offset) as u64 + handle.total_size;
+        (end <= extent).then_some((
+            offset,
+            handle.offset != offset || handle.total_size != extent,
+        ))
+    }
+
     /// Acknowledge that data has been processed and can be cleaned up
+    ///
+    /// Acknowledging a [slice](BlobHandle::slice) does nothing: it only
+    /// stands for the entry, whose own handle is acknowledged once. A
+    /// holder that [`retain`](Self::retain)ed a slice drops that reference
+    /// with [`release`](Self::release). Returns `true` if the slice is valid.
+    ///
+    /// A blob spanning pages is acknowledged on every page, like `release`.
     pub fn acknowledge(&self, handle: &BlobHandle) -> bool {
+        let slice = {
+            let backend = self.backend.read();
+            let Some(page) = backend.get_page(handle.page_id) else {
+                return false;
+            };
+            if page.generation != handle.generation {
+                return false;
+            }
+            match Self::locate(page, handle) {
+                Some((_, slice)) => slice,
+                None => return false,
+            }
+        };
+        slice || self.for_each_entry(handle, false, Page::acknowledge_entry)
+    }
+
+    /// Take another reference to an entry, for one more holder that will
+    /// [`release`](Self::release) it.
+    ///
+    /// Every entry starts with one reference, which `acknowledge` or
+    /// `release` drops (not both). Once the count reaches zero the entry
+    /// is done, like an acknowledged one, and can no longer be retained.
+    /// References do not extend the TTL; see [`touch`](Self::touch).
+    ///
+    /// A [slice](BlobHandle::slice) stands for its whole entry: retain one
+    /// before handing it on, so the entry outlives every slice in use, and
+    /// release it once done; acknowledging a slice drops no reference.
+    ///
+    /// Returns `false` if the handle is stale, the entry has expired or
+    /// its count already reached zero.
+    pub fn retain(&self, handle: &BlobHandle) -> bool {
+        let ttl_ms = self.config.default_ttl_ms;
+        self.for_each_entry(handle, true, |page, offset| {
+            page.retain_entry(offset, ttl_ms)
+        })
+    }
+
+    /// Drop a reference to an entry taken by `append` or
+    /// [`retain`](Self::retain). The entry is done once the last one goes.
+    ///
+    /// Returns `false` if the handle is stale or no reference is left.
+    pub fn release(&self, handle: &BlobHandle) -> bool {
+        self.for_each_entry(handle, true, Page::release_entry)
+    }
+
+    /// Run `f` on the entry `handle` refers to, then, if it succeeded, on
+    /// the entries of its continuation pages (one each, at offset 0).
+    /// Fails for slices of the entry unless `slices` is set.
+    fn for_each_entry(
+        &self,
+        handle: &BlobHandle,
+        slices: bool,
+        f: impl Fn(&Page, u32) -> bool,
+    ) -> bool {
         let backend = self.backend.read();
-        if let Some(page) = backend.get_page(handle.page_id) {
-            if page.generation == handle.generation {
-                return page.acknowledge_entry(handle.offset);
+        let Some(first) = backend.get_page(handle.page_id) else {
+            return false;
+        };
+        if first.generation != handle.generation {
+            return false;
+        }
+        match Self::locate(first, handle) {
+            Some((offset, slice)) if slices || !slice => {
+                if !f(first, offset) {
+                    return false;
+                }
             }
+            _ => return false,
         }
-        false
+        for page_id in handle.page_id + 1..=handle.end_page_id {
+            if let Some(page) = backend.get_page(page_id) {
+                f(page, 0);
+            }
+        }
+        true
+    }
+
+    /// Renew the lease on an entry: push its expiry out to at least
+    /// `extend_ms` from now. Pass [`TTL_NEVER`](crate::TTL_NEVER) to keep
+    /// it until it is acknowledged.
+    ///
+    /// Returns `false` if the handle is stale or the entry has already
+    /// expired. Never shortens an entry's lifetime.
+    pub fn touch(&self, handle: &BlobHandle, extend_ms: u64) -> bool {
+        let expires_at = now_ms().saturating_add(extend_ms);
+        let ttl_ms = self.config.default_ttl_ms;
+        self.for_each_entry(handle, true, |page, offset| {
+            page.touch_entry(offset, expires_at, ttl_ms)
+        })
+    }
+
+    /// Delete an entry: `get` fails for it from now on, and it counts as
+    /// acknowledged for reclamation even if references to it are still
+    /// held. The bytes stay in the page until it is freed; use
+    /// [`purge`](Self::purge) to overwrite them.
+    ///
+    /// Returns `false` if the handle is stale.
+    pub fn delete(&self, handle: &BlobHandle) -> bool {
+        self.for_each_entry(handle, false, |page, offset| {
+            page.delete_entry(offset, false)
+        })
+    }
+
+    /// Delete an entry like [`delete`](Self::delete) and overwrite its bytes
+    /// with zeros, for data that must not linger in memory.
+    ///
+    /// Returns `false` if the handle is stale.
+    pub fn purge(&self, handle: &BlobHandle) -> bool {
+        self.for_each_entry(handle, false, |page, offset| {
+            page.delete_entry(offset, true)
+        })
     }
 
     /// Clean up acknowledged and expired entries
@@ -398,8 +960,14 @@ impl PinnedBlobStore {
     /// or expired for cleanup. Pages that are empty, successfully decayed,
     /// AND strictly not equal to the current active page are removed from memory.
     ///
+    /// The current page is rotated out first if it holds data, every entry
+    /// in it is done, and it has stayed that way for the decay timeout; it
+    /// then decays on a later call like any other page.
+    ///
     /// Returns the number of pages that were freed.
     pub fn cleanup_acknowledged(&self) -> usize {
+        self.rotate_idle_page();
+
         // We need a write lock to remove pages
         let mut backend = self.backend.write();
 
@@ -419,7 +987,7 @@ impl PinnedBlobStore {
             }
 
             // We must first get a reference to check status
-            let (should_remove, used_bytes) = if let Some(page) = backend.get_page(page_id) {
+            let (should_remove, used_bytes, dead) = if let Some(page) = backend.get_page(page_id) {
                 // 1. Mark entries as empty/acknowledged
                 page.mark_empty_if_needed(self.config.default_ttl_ms);
 
@@ -428,12 +996,19 @@ impl PinnedBlobStore {
                     page.should_decay(self.config.decay_timeout_ms, self.config.default_ttl_ms);
 
                 // Capture usage statistics before the page is dropped
-                let usage_ratio = page.usage();
-                let used_approx = (usage_ratio * self.config.page_size as f32) as usize;
+                let padding = page.padding_bytes();
+                let used_data = page.used_bytes() - padding;
+
+                // Entries still holding a reference are dropped unacknowledged
+                let dead = if decay {
+                    page.dead_letters()
+                } else {
+                    Vec::new()
+                };
 
-                (decay, used_approx)
+                (decay, (used_data, padding), dead)
             } else {
-                (false, 0)
+                (false, (0, 0), Vec::new())
             };
 
             if should_remove {
@@ -449,8 +1024,13 @@ impl PinnedBlobStore {
 
                     // Record actual memory freed (approximate based on page size)
                     // We use full page size because the entire allocation is dropped
+                    let (used_data, padding) = used_bytes;
                     self.profiler
-                        .record_page_cleanup(self.config.page_size, used_bytes);
+                        .record_page_cleanup(self.config.page_size, used_data, padding);
+
+                    for letter in dead {
+                        self.report_dead_letter(letter);
+                    }
                 }
             }
         }
@@ -462,6 +1042,38 @@ impl PinnedBlobStore {
         freed_pages
     }
 
+    /// Move `current_page` off a page that is non-empty, fully acknowledged
+    /// or expired, and has decayed, so `cleanup_acknowledged` may free it.
+    ///
+    /// Appends clear a page's `empty_since`, so a page that keeps receiving
+    /// data never decays and is never rotated. An append that races the
+    /// rotation lands in the old page, which then is not empty and is kept.
+    fn rotate_idle_page(&self) {
+        let current_page_id = self.current_page.load(Ordering::Acquire);
+        {
+            let backend = self.backend.read();
+            let Some(page) = backend.get_page(current_page_id) else {
+                return;
+            };
+            if page.usage() == 0.0 {
+                return;
+            }
+            page.mark_empty_if_needed(self.config.default_ttl_ms);
+            if !page.should_decay(self.config.decay_timeout_ms, self.config.default_ttl_ms) {
+                return;
+            }
+        }
+
+        if let Ok(next_page_id) = self.allocate_next_available_page() {
+            let _ = self.current_page.compare_exchange(
+                current_page_id,
+                next_page_id,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            );
+        }
+    }
+
     /// Get access to the profiler for metrics
     pub fn profiler(&self) -> &Profiler {
         &self.profiler
@@ -488,7 +1100,7 @@ impl PinnedBlobStore {
 
     /// Append data to the **shared** overflow arena.
     ///
-    /// Returns an `OverflowHandle` (24 bytes, `#[repr(C)]`) suitable for
+    /// Returns an `OverflowHandle` (48 bytes, `#[repr(C)]`) suitable for
     /// embedding in a ring-buffer slot payload. Any process that has
     /// attached to the same namespace can `resolve()` this handle.
     ///
@@ -500,17 +1112,179 @@ impl PinnedBlobStore {
             .append(data)
     }
 
+    /// Append data to the **shared** arena at an address that is a
+    /// multiple of `align`; see [`SharedBackend::append_aligned`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn append_shared_aligned(&self, data: &[u8], align: usize) -> Result<OverflowHandle> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .append_aligned(data, align)
+    }
+
+    /// Append the concatenation of `bufs` to the **shared** arena as a
+    /// single entry; see [`SharedBackend::append_vectored`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn append_shared_vectored(&self, bufs: &[IoSlice<'_>]) -> Result<OverflowHandle> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .append_vectored(bufs)
+    }
+
+    /// Reserve room for a **shared** entry written in place; see
+    /// [`SharedBackend::reserve`].
+    ///
+    /// Requires an arena created with `ArenaLayout::entry_records`.
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn reserve_shared(&self, len: usize) -> Result<SharedWriteSlot<'_>> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .reserve(len)
+    }
+
+    /// Start a **shared** entry of unknown length; see
+    /// [`SharedBackend::writer`].
+    ///
+    /// Requires an arena created with `ArenaLayout::entry_records`.
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn writer_shared(&self) -> Result<SharedBlobWriter<'_>> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .writer()
+    }
+
+    /// Renew the lease on a **shared** entry; see [`SharedBackend::touch`].
+    ///
+    /// Requires an arena created with `ArenaLayout::entry_records`.
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn touch_shared(&self, handle: &OverflowHandle, extend_ms: u64) -> Result<()> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .touch(handle, extend_ms)
+    }
+
+    /// Append data to the **shared** arena with its own TTL in place of the
+    /// namespace's shared TTL.
+    ///
+    /// Requires an arena created with `ArenaLayout::entry_records`;
+    /// otherwise returns `Err(LayoutFeatureRequired)`.
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn append_shared_with_ttl(&self, data: &[u8], ttl_ms: u64) -> Result<OverflowHandle> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .append_with_ttl(data, ttl_ms)
+    }
+
+    /// Append data to the shared namespace, inlining payloads of up to
+    /// 16 bytes in the handle itself instead of writing them to a chunk.
+    ///
+    /// `resolve()` and `acknowledge_shared()` handle both kinds, so producers
+    /// can call this for every payload size.
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn append_shared_auto(&self, data: &[u8]) -> Result<OverflowHandle> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .append_auto(data)
+    }
+
     /// Resolve an `OverflowHandle` to an owned copy of the data.
     ///
     /// The data is copied out of the mmapped `/dev/shm` region so it
     /// remains valid even if the underlying chunk is recycled.
     ///
-    /// Returns `None` if the handle is expired, the generation doesn't match,
-    /// or the store is not in shared mode.
+    /// Returns `None` if the handle is expired (per the namespace's shared
+    /// TTL), the generation doesn't match, or the store is not in shared mode.
     pub fn resolve(&self, handle: &OverflowHandle) -> Option<Vec<u8>> {
         self.shared
             .as_ref()
-            .and_then(|s| s.resolve(handle, self.config.default_ttl_ms))
+            .and_then(|s| s.resolve(handle, s.config().ttl_ms))
+    }
+
+    /// Like [`resolve`](Self::resolve), but reports why the handle was
+    /// rejected: `HandleExpired`, `WrongInstance` (issued by another
+    /// instance of the namespace) or `InvalidHandle`.
+    pub fn try_resolve(&self, handle: &OverflowHandle) -> Result<Vec<u8>> {
+        let s = self.shared.as_ref().ok_or(BlobError::InvalidHandle)?;
+        s.try_resolve(handle, s.config().ttl_ms)
+    }
+
+    /// Copy part of a **shared** entry into `buf`, on the namespace's
+    /// shared TTL; see [`SharedBackend::read_at`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn read_at_shared(
+        &self,
+        handle: &OverflowHandle,
+        offset: u64,
+        buf: &mut [u8],
+    ) -> Result<usize> {
+        let s = self.shared.as_ref().ok_or(BlobError::InvalidHandle)?;
+        s.read_at(handle, s.config().ttl_ms, offset, buf)
+    }
+
+    /// Resolve a **shared** entry into `buf`, on the namespace's shared
+    /// TTL; see [`SharedBackend::get_into`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn get_into_shared(&self, handle: &OverflowHandle, buf: &mut Vec<u8>) -> Result<usize> {
+        let s = self.shared.as_ref().ok_or(BlobError::InvalidHandle)?;
+        s.get_into(handle, s.config().ttl_ms, buf)
+    }
+
+    /// Open a reader over a **shared** entry, on the namespace's shared
+    /// TTL; see [`SharedBackend::open_reader`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn open_reader_shared(&self, handle: &OverflowHandle) -> Result<SharedBlobReader<'_>> {
+        let s = self.shared.as_ref().ok_or(BlobError::InvalidHandle)?;
+        s.open_reader(handle, s.config().ttl_ms)
+    }
+
+    /// Slice a **shared** entry so that authenticated arenas accept the
+    /// slice; see [`SharedBackend::slice`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn slice_shared(
+        &self,
+        handle: &OverflowHandle,
+        range: impl RangeBounds<u64>,
+    ) -> Result<OverflowHandle> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .slice(handle, range)
+    }
+
+    /// Append one `T` to the **shared** arena; see
+    /// [`SharedBackend::append_value`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn append_value_shared<T: Pod>(&self, value: &T) -> Result<Handle<T, OverflowHandle>> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .append_value(value)
+    }
+
+    /// Read a `T` from the **shared** arena, on the namespace's shared TTL;
+    /// see [`SharedBackend::view`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn view_shared<T: Pod>(
+        &self,
+        handle: &Handle<T, OverflowHandle>,
+    ) -> Result<SharedValueRef<T>> {
+        let s = self.shared.as_ref().ok_or(BlobError::InvalidHandle)?;
+        s.view(handle, s.config().ttl_ms)
     }
 
     /// Acknowledge a shared-mode entry.
@@ -521,14 +1295,213 @@ impl PinnedBlobStore {
             .unwrap_or(false)
     }
 
+    /// Like [`acknowledge_shared`](Self::acknowledge_shared), but reports
+    /// why the handle was rejected (`WrongInstance` or `InvalidHandle`).
+    pub fn try_acknowledge_shared(&self, handle: &OverflowHandle) -> Result<()> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .try_acknowledge(handle)
+    }
+
+    /// Take another reference to a **shared** entry; see
+    /// [`SharedBackend::retain`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn retain_shared(&self, handle: &OverflowHandle) -> Result<()> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .retain(handle)
+    }
+
+    /// Drop a reference to a **shared** entry; see
+    /// [`SharedBackend::release`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn release_shared(&self, handle: &OverflowHandle) -> Result<()> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .release(handle)
+    }
+
+    /// Delete a **shared** entry so it can no longer be resolved; see
+    /// [`SharedBackend::delete`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn delete_shared(&self, handle: &OverflowHandle) -> Result<()> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .delete(handle)
+    }
+
+    /// Delete a **shared** entry and zero its payload; see
+    /// [`SharedBackend::purge`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn purge_shared(&self, handle: &OverflowHandle) -> Result<()> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .purge(handle)
+    }
+
+    /// Claim a **shared** entry for `consumer` for `visibility_timeout_ms`;
+    /// see [`SharedBackend::claim`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn claim_shared(
+        &self,
+        handle: &OverflowHandle,
+        consumer: u16,
+        visibility_timeout_ms: u64,
+    ) -> Result<()> {
+        self.shared.as_ref().ok_or(BlobError::InvalidHandle)?.claim(
+            handle,
+            consumer,
+            visibility_timeout_ms,
+        )
+    }
+
+    /// Queue every shared entry whose claim has lapsed for redelivery
+    /// through [`next_redelivery`](Self::next_redelivery). Called by each
+    /// maintenance cycle.
+    ///
+    /// Returns the number of entries queued (0 in heap mode).
+    pub fn redeliver_expired_claims(&self) -> usize {
+        let Some(shared) = self.shared.as_ref() else {
+            return 0;
+        };
+        let lapsed = shared.take_expired_claims();
+        for handle in &lapsed {
+            // The receiver lives in `self`, so the queue is never closed
+            let _ = self.redelivery.0.send(*handle);
+        }
+        lapsed.len()
+    }
+
+    /// Next shared entry whose claim lapsed without an ack, if any. Claim
+    /// it again before processing it.
+    pub fn next_redelivery(&self) -> Option<OverflowHandle> {
+        self.redelivery.1.try_recv().ok()
+    }
+
+    /// Next entry that was freed before it was acknowledged, if any, oldest
+    /// first. Heap pages report theirs when `cleanup_acknowledged` frees
+    /// them, shared chunks when `cleanup_shared` does (arenas with entry
+    /// records only).
+    ///
+    /// At most [`DEAD_LETTER_CAPACITY`] are queued; later ones are only
+    /// counted in [`ProfileStats`](crate::ProfileStats).
+    pub fn next_dead_letter(&self) -> Option<DeadLetter> {
+        self.dead_letters.1.try_recv().ok()
+    }
+
+    /// Count a dead letter and queue it, dropping it if the queue is full.
+    fn report_dead_letter(&self, letter: DeadLetter) {
+        self.profiler
+            .record_dead_letter(letter.entries(), letter.size);
+        let _ = self.dead_letters.0.try_send(letter);
+    }
+
+    /// Register a consumer group in the shared arena, or look up its id;
+    /// see [`SharedBackend::register_group`].
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn register_group_shared(&self, name: &str) -> Result<u32> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .register_group(name)
+    }
+
+    /// Acknowledge a shared-mode entry on behalf of consumer group `group`.
+    pub fn acknowledge_group_shared(&self, handle: &OverflowHandle, group: u32) -> bool {
+        self.shared
+            .as_ref()
+            .map(|s| s.acknowledge_group(handle, group))
+            .unwrap_or(false)
+    }
+
+    /// Like [`acknowledge_group_shared`](Self::acknowledge_group_shared),
+    /// but reports why the handle or group was rejected.
+    pub fn try_acknowledge_group_shared(&self, handle: &OverflowHandle, group: u32) -> Result<()> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .try_acknowledge_group(handle, group)
+    }
+
+    /// Pack a handle into the 16-byte `CompactHandle` encoding.
+    ///
+    /// Returns `Err(NotCompactable)` if it exceeds the compact limits or
+    /// the arena authenticates handles, and `Err(InvalidHandle)` if the
+    /// store is not in shared mode.
+    pub fn compact_handle(&self, handle: &OverflowHandle) -> Result<CompactHandle> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .compact(handle)
+    }
+
+    /// Rebuild the full `OverflowHandle` from a `CompactHandle`; see
+    /// [`SharedBackend::expand`] for the checks it cannot make.
+    pub fn expand_handle(&self, compact: &CompactHandle) -> Result<OverflowHandle> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .expand(compact)
+    }
+
+    /// Resolve a `CompactHandle`; same rules as [`resolve`](Self::resolve).
+    pub fn resolve_compact(&self, compact: &CompactHandle) -> Option<Vec<u8>> {
+        self.shared
+            .as_ref()
+            .and_then(|s| s.resolve_compact(compact, s.config().ttl_ms))
+    }
+
+    /// Acknowledge a shared-mode entry through its `CompactHandle`.
+    pub fn acknowledge_compact(&self, compact: &CompactHandle) -> bool {
+        self.shared
+            .as_ref()
+            .map(|s| s.acknowledge_compact(compact))
+            .unwrap_or(false)
+    }
+
     /// Run cleanup on shared chunks, recycling fully-acknowledged ones.
+    ///
+    /// Uses the TTL and decay timeout from the namespace's shared policy.
     pub fn cleanup_shared(&self) -> usize {
         self.shared
             .as_ref()
-            .map(|s| s.cleanup_chunks(self.config.default_ttl_ms, self.config.decay_timeout_ms))
+            .map(|s| {
+                let config = s.config();
+                let freed = s.cleanup_chunks(config.ttl_ms, config.decay_timeout_ms);
+                for letter in s.take_dead_letters() {
+                    self.report_dead_letter(letter);
+                }
+                freed
+            })
             .unwrap_or(0)
     }
 
+    /// Current shared policy of the namespace, or `None` in heap mode.
+    pub fn shared_config(&self) -> Option<SharedConfig> {
+        self.shared.as_ref().map(|s| s.config())
+    }
+
+    /// Update the shared policy for every process attached to the namespace.
+    ///
+    /// Returns `Err(InvalidHandle)` if the store is not in shared mode.
+    pub fn set_shared_config(&self, config: &SharedConfig) -> Result<()> {
+        self.shared
+            .as_ref()
+            .ok_or(BlobError::InvalidHandle)?
+            .set_config(config)
+    }
+
     /// Print debug info about all mapped shared chunks.
     pub fn debug_chunks(&self) {
         if let Some(s) = self.shared.as_ref() {
@@ -539,6 +1512,276 @@ impl PinnedBlobStore {
     }
 }
 
+/// Space reserved by [`PinnedBlobStore::reserve`], written in place through
+/// `DerefMut<Target = [u8]>`. It starts zeroed.
+///
+/// [`commit`](Self::commit) publishes the entry. Dropping the slot
+/// uncommitted deletes it instead, so it never becomes readable and counts
+/// as acknowledged.
+pub struct WriteSlot<'a> {
+    store: &'a PinnedBlobStore,
+    handle: BlobHandle,
+    ptr: *mut u8,
+    committed: bool,
+}
+
+impl WriteSlot<'_> {
+    /// Publish the entry and return its handle. The default TTL runs from
+    /// the commit, not the reservation.
+    ///
+    /// Returns `Err(InvalidHandle)` if the entry was deleted meanwhile.
+    pub fn commit(mut self) -> Result<BlobHandle> {
+        self.committed = true;
+        self.store.commit_reserved(&self.handle)
+    }
+}
+
+impl std::ops::Deref for WriteSlot<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the slot owns the reserved range until it is committed or
+        // dropped, and the pending entry keeps the page alive.
+        unsafe { std::slice::from_raw_parts(self.ptr, self.handle.size as usize) }
+    }
+}
+
+impl std::ops::DerefMut for WriteSlot<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as for `deref`; nobody else writes the range.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.handle.size as usize) }
+    }
+}
+
+impl Drop for WriteSlot<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.store.delete(&self.handle);
+        }
+    }
+}
+
+impl std::fmt::Debug for WriteSlot<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteSlot")
+            .field("page_id", &self.handle.page_id)
+            .field("offset", &self.handle.offset)
+            .field("len", &self.handle.size)
+            .finish()
+    }
+}
+
+// A slot may be filled on another thread: it owns its range exclusively
+unsafe impl Send for WriteSlot<'_> {}
+
+/// Writer for a blob of unknown length, from [`PinnedBlobStore::writer`].
+///
+/// Bytes go straight into pages reserved for the writer, which nobody can
+/// read until [`finish`](Self::finish). Dropping the writer unfinished
+/// deletes them, so their pages can be reclaimed.
+pub struct BlobWriter<'a> {
+    store: &'a PinnedBlobStore,
+    /// Pages written so far, each reserved whole, with their data
+    pages: Vec<(u32, *mut u8)>,
+    /// Bytes written so far
+    len: u64,
+    finished: bool,
+}
+
+impl BlobWriter<'_> {
+    /// Bytes written so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// True if nothing has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Publish everything written and return one handle covering it.
+    ///
+    /// Returns `Err(DataTooLarge)` if nothing was written.
+    pub fn finish(mut self) -> Result<BlobHandle> {
+        if self.len == 0 {
+            return Err(BlobError::DataTooLarge {
+                size: 0,
+                max: self.store.config.page_size,
+            });
+        }
+        let (handle, in_place) = self.store.finish_writer(&mut self.pages, self.len)?;
+        // Pages that were copied from are released on drop
+        self.finished = in_place;
+        Ok(handle)
+    }
+}
+
+impl std::io::Write for BlobWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let page_size = self.store.config.page_size;
+        if self.len == (self.pages.len() * page_size) as u64 {
+            let page = self
+                .store
+                .allocate_writer_page()
+                .map_err(std::io::Error::other)?;
+            self.pages.push(page);
+        }
+        let (_, ptr) = self.pages[self.pages.len() - 1];
+        let at = self.len as usize - (self.pages.len() - 1) * page_size;
+        let n = buf.len().min(page_size - at);
+        // SAFETY: the writer owns the page's reserved range, and
+        // `at + n` stays within it.
+        unsafe { std::ptr::copy_nonoverlapping(buf.as_ptr(), ptr.add(at), n) };
+        self.len += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for BlobWriter<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let backend = self.store.backend.read();
+        for &(page_id, _) in &self.pages {
+            if let Some(page) = backend.get_page(page_id) {
+                page.delete_entry(0, false);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for BlobWriter<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlobWriter")
+            .field("pages", &self.pages.len())
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+// A writer may move to another thread: its pages are its own
+unsafe impl Send for BlobWriter<'_> {}
+
+/// Reader over a stored blob, from [`PinnedBlobStore::open_reader`].
+///
+/// Reads copy from the pages one fragment at a time, so a read may return
+/// fewer bytes than asked for; `read_exact` and `io::copy` handle that.
+/// The store's locks are only held during each read.
+#[derive(Debug, Clone)]
+pub struct BlobReader<'a> {
+    store: &'a PinnedBlobStore,
+    handle: BlobHandle,
+    pos: u64,
+}
+
+impl BlobReader<'_> {
+    /// Size of the blob in bytes.
+    pub fn len(&self) -> u64 {
+        self.handle.total_size
+    }
+
+    /// True if the blob is empty.
+    pub fn is_empty(&self) -> bool {
+        self.handle.total_size == 0
+    }
+
+    /// Handle of the blob being read.
+    pub fn handle(&self) -> &BlobHandle {
+        &self.handle
+    }
+}
+
+impl std::io::Read for BlobReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self
+            .store
+            .read_fragment(&self.handle, self.pos, buf)
+            .map_err(std::io::Error::other)?;
+        if n > 0 {
+            self.store.profiler.record_read(n);
+        }
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl std::io::Seek for BlobReader<'_> {
+    fn seek(&mut self, to: SeekFrom) -> std::io::Result<u64> {
+        self.pos = seek_position(self.handle.total_size, self.pos, to)?;
+        Ok(self.pos)
+    }
+
+    fn stream_position(&mut self) -> std::io::Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+/// A `T` read from the store, from [`PinnedBlobStore::view`].
+/// Dereferences to the value.
+pub struct ValueRef<'a, T: Pod> {
+    inner: Value<'a, T>,
+}
+
+enum Value<'a, T: Pod> {
+    /// In place; the page is pinned until the view is dropped
+    Borrowed {
+        store: &'a PinnedBlobStore,
+        page_id: u32,
+        ptr: *const T,
+    },
+    /// Copied out
+    Owned(T),
+}
+
+impl<T: Pod> ValueRef<'_, T> {
+    /// True if the value is read in place rather than from a copy.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.inner, Value::Borrowed { .. })
+    }
+}
+
+impl<T: Pod> std::ops::Deref for ValueRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match &self.inner {
+            // SAFETY: aligned and in bounds, and the pin keeps the page
+            // alive and its bytes unchanged; any bytes are a valid `T`
+            Value::Borrowed { ptr, .. } => unsafe { &**ptr },
+            Value::Owned(value) => value,
+        }
+    }
+}
+
+impl<T: Pod> Drop for ValueRef<'_, T> {
+    fn drop(&mut self) {
+        if let Value::Borrowed { store, page_id, .. } = self.inner {
+            store.unpin_page(page_id);
+        }
+    }
+}
+
+impl<T: Pod + std::fmt::Debug> std::fmt::Debug for ValueRef<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValueRef")
+            .field("value", &**self)
+            .field("borrowed", &self.is_borrowed())
+            .finish()
+    }
+}
+
+// The pinned bytes are only read, and unpinning works from any thread
+unsafe impl<T: Pod + Sync> Send for ValueRef<'_, T> {}
+unsafe impl<T: Pod + Sync> Sync for ValueRef<'_, T> {}
+
 /// Statistics about the blob store
 #[derive(Debug, Clone)]
 pub struct BlobStats {
@@ -8,7 +8,8 @@
 //!
 //! - **Pages**: allocated, freed, active (derived)
 //! - **Operations**: appends, reads, cleanups, multi-page spans
-//! - **Data volume**: bytes written, read, discarded
+//! - **Data volume**: bytes written, read, discarded, alignment padding
+//! - **Dead letters**: entries (and their bytes) that expired unacknowledged
 //! - **Capacity**: allocated, freed, fragmentation ratio
 //! - **Uptime**: seconds since profiler creation
 //!
@@ -39,6 +40,11 @@ pub struct ProfileStats {
     pub total_bytes_written: u64,
     pub total_bytes_read: u64,
     pub total_bytes_discarded: u64, // Data in freed pages
+    pub total_bytes_padded: u64,    // Skipped to align payloads
+
+    // Dead Letters (expired unacknowledged)
+    pub dead_letters: u64,
+    pub dead_letter_bytes: u64,
 
     // Capacity Volume
     pub total_capacity_allocated: u64,
@@ -48,7 +54,8 @@ pub struct ProfileStats {
     pub active_pages: usize,
     pub active_capacity_bytes: u64, // Total size of active pages
     pub active_data_bytes: u64,     // Actual data stored in active pages
-    pub free_space_bytes: u64,      // Capacity - Data
+    pub active_padding_bytes: u64,  // Alignment padding in active pages
+    pub free_space_bytes: u64,      // Capacity - Data (padding included)
     pub fragmentation_ratio: f64,   // Free / Capacity
     pub uptime_secs: u64,
 }
@@ -57,21 +64,17 @@ impl ProfileStats {
     /// Average append size in bytes
     #[inline]
     pub fn avg_append_size(&self) -> u64 {
-        if self.total_appends > 0 {
-            self.total_bytes_written / self.total_appends
-        } else {
-            0
-        }
+        self.total_bytes_written
+            .checked_div(self.total_appends)
+            .unwrap_or(0)
     }
 
     /// Average read size in bytes
     #[inline]
     pub fn avg_read_size(&self) -> u64 {
-        if self.total_reads > 0 {
-            self.total_bytes_read / self.total_reads
-        } else {
-            0
-        }
+        self.total_bytes_read
+            .checked_div(self.total_reads)
+            .unwrap_or(0)
     }
 }
 
@@ -104,6 +107,12 @@ struct ProfilerState {
     total_bytes_written: AtomicU64,
     total_bytes_read: AtomicU64,
     total_bytes_discarded: AtomicU64,
+    total_bytes_padded: AtomicU64,
+    total_padding_discarded: AtomicU64,
+
+    // Dead letters
+    dead_letters: AtomicU64,
+    dead_letter_bytes: AtomicU64,
 
     // Capacity
     total_capacity_allocated: AtomicU64,
@@ -126,6 +135,10 @@ impl Profiler {
                 total_bytes_written: AtomicU64::new(0),
                 total_bytes_read: AtomicU64::new(0),
                 total_bytes_discarded: AtomicU64::new(0),
+                total_bytes_padded: AtomicU64::new(0),
+                total_padding_discarded: AtomicU64::new(0),
+                dead_letters: AtomicU64::new(0),
+                dead_letter_bytes: AtomicU64::new(0),
                 total_capacity_allocated: AtomicU64::new(0),
                 total_capacity_freed: AtomicU64::new(0),
                 start_time: Instant::now(),
@@ -140,7 +153,10 @@ impl Profiler {
     /// values use `saturating_sub` to avoid underflow if a free is
     /// observed before its corresponding allocation.
     ///
-    /// Time: O(1) — reads ~11 atomic counters and computes derived values.
+    /// Padding skipped to align payloads is not data, so it counts as
+    /// free space (and fragmentation) until its page is freed.
+    ///
+    /// Time: O(1) — reads ~15 atomic counters and computes derived values.
     pub fn stats(&self) -> ProfileStats {
         let allocated_pages = self.state.total_pages_allocated.load(Ordering::Acquire);
         let freed_pages = self.state.total_pages_freed.load(Ordering::Acquire);
@@ -150,10 +166,13 @@ impl Profiler {
 
         let written = self.state.total_bytes_written.load(Ordering::Acquire);
         let discarded = self.state.total_bytes_discarded.load(Ordering::Acquire);
+        let padded = self.state.total_bytes_padded.load(Ordering::Acquire);
+        let padding_discarded = self.state.total_padding_discarded.load(Ordering::Acquire);
 
         let active_pages = allocated_pages.saturating_sub(freed_pages);
         let active_cap = allocated_cap.saturating_sub(freed_cap);
         let active_data = written.saturating_sub(discarded);
+        let active_padding = padded.saturating_sub(padding_discarded);
         let free_space = active_cap.saturating_sub(active_data);
 
         let fragmentation = if active_cap > 0 {
@@ -173,6 +192,10 @@ impl Profiler {
             total_bytes_written: written,
             total_bytes_read: self.state.total_bytes_read.load(Ordering::Acquire),
             total_bytes_discarded: discarded,
+            total_bytes_padded: padded,
+
+            dead_letters: self.state.dead_letters.load(Ordering::Acquire),
+            dead_letter_bytes: self.state.dead_letter_bytes.load(Ordering::Acquire),
 
             total_capacity_allocated: allocated_cap,
             total_capacity_freed: freed_cap,
@@ -180,6 +203,7 @@ impl Profiler {
             active_pages,
             active_capacity_bytes: active_cap,
             active_data_bytes: active_data,
+            active_padding_bytes: active_padding,
             free_space_bytes: free_space,
             fragmentation_ratio: fragmentation,
 
@@ -195,6 +219,13 @@ impl Profiler {
             .fetch_add(size as u64, Ordering::Relaxed);
     }
 
+    /// Record padding skipped to align an append. Time: O(1).
+    pub fn record_padding(&self, size: usize) {
+        self.state
+            .total_bytes_padded
+            .fetch_add(size as u64, Ordering::Relaxed);
+    }
+
     /// Record a read operation. Time: O(1).
     pub fn record_read(&self, size: usize) {
         self.state.total_reads.fetch_add(1, Ordering::Relaxed);
@@ -213,8 +244,9 @@ impl Profiler {
             .fetch_add(capacity as u64, Ordering::Relaxed);
     }
 
-    /// Record a page cleanup (freed). Time: O(1).
-    pub fn record_page_cleanup(&self, capacity: usize, used_data: usize) {
+    /// Record a page cleanup (freed), holding `used_data` bytes of data
+    /// and `padding` bytes of alignment padding. Time: O(1).
+    pub fn record_page_cleanup(&self, capacity: usize, used_data: usize, padding: usize) {
         self.state.total_pages_freed.fetch_add(1, Ordering::Relaxed);
         self.state
             .total_capacity_freed
@@ -222,6 +254,20 @@ impl Profiler {
         self.state
             .total_bytes_discarded
             .fetch_add(used_data as u64, Ordering::Relaxed);
+        self.state
+            .total_padding_discarded
+            .fetch_add(padding as u64, Ordering::Relaxed);
+    }
+
+    /// Record `entries` dropped unacknowledged, `size` bytes in all.
+    /// Time: O(1).
+    pub fn record_dead_letter(&self, entries: u64, size: u64) {
+        self.state
+            .dead_letters
+            .fetch_add(entries, Ordering::Relaxed);
+        self.state
+            .dead_letter_bytes
+            .fetch_add(size, Ordering::Relaxed);
     }
 
     /// Record a cleanup cycle. Time: O(1).
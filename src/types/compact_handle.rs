@@ -0,0 +1,169 @@
+/// Compact 16-byte cross-process handle for tight ring-buffer slots.
+///
+/// Carries only what the arena cannot reconstruct: page id, offset, size,
+/// the low 16 bits of the generation and the creation time as a delta from
+/// the chunk's `first_write_ts`. The instance id is dropped and taken from
+/// the store on expansion, so converting an
+/// [`OverflowHandle`](super::OverflowHandle) to a `CompactHandle` and back
+/// through the same namespace is lossless.
+///
+/// Two checks of the full handle are lost in the compact form. There is no
+/// room for a tag, so arenas with authenticated handles have no compact
+/// form. And since the instance id is not carried, a compact handle kept
+/// from an earlier instance of a recreated namespace is never rejected
+/// with `WrongInstance`; only the low 16 bits of the generation tell the
+/// instances apart.
+///
+/// # Layout
+/// 128 bits, stored as two native-endian `u64` words (low word first):
+///
+/// | Bits | Field | Limit |
+/// |------|-------|-------|
+/// | 0..32 | size | 4 GB |
+/// | 32..52 | page_id | 1,048,575 |
+/// | 52..88 | offset | 64 GB |
+/// | 88..104 | generation (low 16 bits) | - |
+/// | 104..128 | timestamp delta (ms) | ~4.6 hours |
+///
+/// Handles with fields beyond these limits have no compact form.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompactHandle {
+    words: [u64; 2],
+}
+
+const SIZE_BITS: u32 = 32;
+const PAGE_ID_BITS: u32 = 20;
+const OFFSET_BITS: u32 = 36;
+const GENERATION_BITS: u32 = 16;
+const DELTA_BITS: u32 = 24;
+
+const PAGE_ID_SHIFT: u32 = SIZE_BITS;
+const OFFSET_SHIFT: u32 = PAGE_ID_SHIFT + PAGE_ID_BITS;
+const GENERATION_SHIFT: u32 = OFFSET_SHIFT + OFFSET_BITS;
+const DELTA_SHIFT: u32 = GENERATION_SHIFT + GENERATION_BITS;
+
+/// Size of the compact encoding in bytes.
+pub const COMPACT_HANDLE_SIZE: usize = 16;
+
+impl CompactHandle {
+    /// Pack the fields, or `None` if any exceeds its bit width.
+    ///
+    /// `generation` is truncated to its low 16 bits; every other field must fit.
+    ///
+    /// Time: O(1).
+    pub(crate) fn pack(
+        page_id: u32,
+        offset: u64,
+        size: u32,
+        generation: u64,
+        timestamp_delta_ms: u64,
+    ) -> Option<Self> {
+        if page_id >> PAGE_ID_BITS != 0
+            || offset >> OFFSET_BITS != 0
+            || timestamp_delta_ms >> DELTA_BITS != 0
+        {
+            return None;
+        }
+        let bits = size as u128
+            | (page_id as u128) << PAGE_ID_SHIFT
+            | (offset as u128) << OFFSET_SHIFT
+            | (generation as u16 as u128) << GENERATION_SHIFT
+            | (timestamp_delta_ms as u128) << DELTA_SHIFT;
+        Some(Self {
+            words: [bits as u64, (bits >> 64) as u64],
+        })
+    }
+
+    fn bits(&self) -> u128 {
+        self.words[0] as u128 | (self.words[1] as u128) << 64
+    }
+
+    fn field(&self, shift: u32, width: u32) -> u64 {
+        ((self.bits() >> shift) & ((1u128 << width) - 1)) as u64
+    }
+
+    /// Chunk index in the shared arena.
+    pub fn page_id(&self) -> u32 {
+        self.field(PAGE_ID_SHIFT, PAGE_ID_BITS) as u32
+    }
+
+    /// Byte offset within the chunk's data region.
+    pub fn offset(&self) -> u64 {
+        self.field(OFFSET_SHIFT, OFFSET_BITS)
+    }
+
+    /// Size of the stored data in bytes.
+    pub fn size(&self) -> u32 {
+        self.field(0, SIZE_BITS) as u32
+    }
+
+    /// Low 16 bits of the chunk generation the handle was issued under.
+    pub fn generation_low(&self) -> u16 {
+        self.field(GENERATION_SHIFT, GENERATION_BITS) as u16
+    }
+
+    /// Creation time in milliseconds after the chunk's `first_write_ts`.
+    pub fn timestamp_delta_ms(&self) -> u64 {
+        self.field(DELTA_SHIFT, DELTA_BITS)
+    }
+
+    /// Serialize this handle to a byte slice (zero-copy view).
+    ///
+    /// Time: O(1) — pointer cast, no copy.
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(
+                self as *const Self as *const u8,
+                std::mem::size_of::<Self>(),
+            )
+        }
+    }
+
+    /// Deserialize a `CompactHandle` from exactly 16 bytes.
+    ///
+    /// Time: O(1) — 16-byte copy.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != COMPACT_HANDLE_SIZE {
+            return None;
+        }
+        Some(Self {
+            words: [
+                u64::from_ne_bytes(bytes[..8].try_into().unwrap()),
+                u64::from_ne_bytes(bytes[8..].try_into().unwrap()),
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_handle_size() {
+        assert_eq!(std::mem::size_of::<CompactHandle>(), COMPACT_HANDLE_SIZE);
+    }
+
+    #[test]
+    fn test_compact_handle_pack_roundtrip() {
+        let max_offset = (1u64 << OFFSET_BITS) - 1;
+        let h = CompactHandle::pack(0xF_FFFF, max_offset, u32::MAX, 0x1_2345, 0xFF_FFFF).unwrap();
+        assert_eq!(h.page_id(), 0xF_FFFF);
+        assert_eq!(h.offset(), max_offset);
+        assert_eq!(h.size(), u32::MAX);
+        assert_eq!(h.generation_low(), 0x2345);
+        assert_eq!(h.timestamp_delta_ms(), 0xFF_FFFF);
+
+        let restored = CompactHandle::from_bytes(h.as_bytes()).unwrap();
+        assert_eq!(h, restored);
+        assert!(CompactHandle::from_bytes(&[0u8; 15]).is_none());
+    }
+
+    #[test]
+    fn test_compact_handle_rejects_wide_fields() {
+        assert!(CompactHandle::pack(1 << 20, 0, 1, 1, 0).is_none());
+        assert!(CompactHandle::pack(0, 1 << 36, 1, 1, 0).is_none());
+        assert!(CompactHandle::pack(0, 0, 1, 1, 1 << 24).is_none());
+    }
+}
@@ -4,16 +4,34 @@
 //!
 //! - [`BlobHandle`] — 32-byte reference to data in the heap-based backend.
 //!   Encodes page ID, offset, size, generation, and multi-page span info.
-//! - [`OverflowHandle`] — 24-byte `#[repr(C)]` reference to data in the
+//! - [`OverflowHandle`] — 48-byte `#[repr(C)]` reference to data in the
 //!   shared-memory backend. ABI-stable for cross-process use; can be
 //!   serialized to bytes and embedded in ring-buffer slot payloads.
+//! - [`HANDLE_WIRE_VERSION`] — Version of the portable little-endian
+//!   encoding of both handle types (`to_bytes`, `Display` / `FromStr`).
+//! - [`CompactHandle`] — 16-byte packed form of an `OverflowHandle` for
+//!   small slots; expanded back through the store that issued it.
 //! - [`Config`] — Tunable parameters (page size, TTL, decay timeout, etc.).
+//! - [`SharedConfig`] — Policy stored in a shared namespace's control file
+//!   so that every attached process agrees on TTL, decay and limits.
+//! - [`ArenaLayout`] — Creation-time choice of the shared on-disk format.
 //! - [`BlobError`] / [`Result`] — Error types for all operations.
 //! - [`BackendMode`] — Enum selecting heap vs shared storage.
 //! - [`now_ms()`] — Safe monotonic-ish timestamp helper used throughout.
+//! - [`TTL_NEVER`] — TTL value that pins an entry until it is acknowledged.
+//! - [`DeadLetter`] — Report of an entry that expired unacknowledged.
+//! - [`Pod`] / [`Handle`] — Marker for plain-old-data types, and the typed
+//!   handle `append_value` returns for them.
 
+mod compact_handle;
 mod overflow_handle;
+#[allow(clippy::module_inception)]
 mod types;
+mod value;
+mod wire;
 
+pub use compact_handle::*;
 pub use overflow_handle::*;
 pub use types::*;
+pub use value::*;
+pub use wire::*;
@@ -1,4 +1,6 @@
-use super::types::now_ms;
+use super::types::{now_ms, slice_bounds};
+use super::wire::OVERFLOW_HANDLE_WIRE_SIZE;
+use std::ops::RangeBounds;
 
 /// Cross-process overflow handle.
 /// Stored directly in the ring buffer slot payload when `overflow == 1`.
@@ -7,27 +9,62 @@ use super::types::now_ms;
 /// the handle against its own mmap of the shared arena — no absolute pointers.
 ///
 /// # Layout
-/// Total size: 24 bytes. ABI-stable across processes.
+/// Total size: 48 bytes. ABI-stable across processes.
+///
+/// Offsets and generations are 64-bit so handles can address chunks larger
+/// than 4 GB (arena layout v2). A single entry is still limited to 4 GB.
+/// The 24-byte layout-v1 encoding is accepted by [`from_bytes`](Self::from_bytes)
+/// and produced by [`to_v1_bytes`](Self::to_v1_bytes) for mixed-version fleets.
+///
+/// # Inline handles
+/// Payloads of up to [`INLINE_CAPACITY`] bytes can travel inside the handle
+/// itself: `page_id` is set to [`INLINE_PAGE_ID`] and the bytes occupy the
+/// `offset` and `generation` fields. Resolving one never touches shared memory.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct OverflowHandle {
     /// Chunk index in the shared arena (0..∞, maps to `/dev/shm/{ns}_data_{page_id}`)
     pub page_id: u32,
-    /// Byte offset within the chunk's data region
-    pub offset: u32,
     /// Size of the stored data in bytes
     pub size: u32,
+    /// Byte offset within the chunk's data region
+    pub offset: u64,
     /// Generation counter — prevents ABA problems when a chunk is recycled
-    pub generation: u32,
+    pub generation: u64,
     /// Creation timestamp (ms since UNIX epoch) — used for TTL expiry
     pub timestamp: u64,
+    /// MAC over `(page_id, offset, size, generation)` in arenas created with
+    /// `authenticated_handles`; 0 otherwise. Not covered: `timestamp`.
+    pub tag: u64,
+    /// Random id of the namespace instance that issued this handle.
+    /// 0 means unknown (legacy producer or arena) and is not checked.
+    pub instance_id: u64,
 }
 
+/// Size of the legacy (layout v1) handle encoding:
+/// `page_id u32 | offset u32 | size u32 | generation u32 | timestamp u64`.
+pub const OVERFLOW_HANDLE_V1_SIZE: usize = 24;
+
+/// Sentinel `page_id` of an inline handle. Never a real chunk id: chunk
+/// allocation stops at `u32::MAX - 1`.
+pub const INLINE_PAGE_ID: u32 = u32::MAX;
+
+/// Largest payload an inline handle can carry (the `offset` and
+/// `generation` fields, 8 bytes each).
+pub const INLINE_CAPACITY: usize = 16;
+
+/// Byte offset of the inline payload within the handle (the `offset` field).
+const INLINE_DATA_START: usize = 8;
+
+/// `tag` of a slice made by [`OverflowHandle::slice`]. Accepted only in
+/// arenas without authenticated handles, where slices carry their own tag.
+pub const SLICE_TAG: u64 = u64::MAX;
+
 impl OverflowHandle {
     /// Create a new overflow handle.
     ///
     /// Time: O(1).
-    pub fn new(page_id: u32, offset: u32, size: u32, generation: u32) -> Self {
+    pub fn new(page_id: u32, offset: u64, size: u32, generation: u64) -> Self {
         let timestamp = now_ms();
 
         Self {
@@ -36,11 +73,94 @@ impl OverflowHandle {
             size,
             generation,
             timestamp,
+            tag: 0,
+            instance_id: 0,
+        }
+    }
+
+    /// Create an inline handle carrying `data` in the handle itself.
+    ///
+    /// Returns `None` if `data` is longer than [`INLINE_CAPACITY`].
+    ///
+    /// Time: O(1).
+    pub fn inline(data: &[u8]) -> Option<Self> {
+        if data.len() > INLINE_CAPACITY {
+            return None;
         }
+        let mut bytes = [0u8; INLINE_CAPACITY];
+        bytes[..data.len()].copy_from_slice(data);
+
+        Some(Self {
+            page_id: INLINE_PAGE_ID,
+            size: data.len() as u32,
+            offset: u64::from_ne_bytes(bytes[..8].try_into().unwrap()),
+            generation: u64::from_ne_bytes(bytes[8..].try_into().unwrap()),
+            timestamp: now_ms(),
+            tag: 0,
+            instance_id: 0,
+        })
+    }
+
+    /// Handle to the bytes `range` of this entry, for passing a section of
+    /// it on without copying. The slice's offset and size point inside the
+    /// entry, and it carries [`SLICE_TAG`], so the backend can tell it is a
+    /// slice and find the entry around it. It is read, renewed and
+    /// reference counted through the whole entry; see
+    /// [`SharedBackend::retain`](crate::SharedBackend::retain).
+    ///
+    /// Arenas with authenticated handles reject the marker, since anyone
+    /// can set it; slice their handles with
+    /// [`SharedBackend::slice`](crate::SharedBackend::slice), which tags
+    /// the slice itself.
+    ///
+    /// Deleting, claiming, group acks and compaction take the entry's own
+    /// handle. Slicing an inline handle gives an inline handle of the
+    /// sub-range. The whole range gives the handle itself.
+    ///
+    /// Returns `None` if the range is empty or out of bounds.
+    ///
+    /// Time: O(1).
+    pub fn slice(&self, range: impl RangeBounds<u64>) -> Option<Self> {
+        let (start, len) = slice_bounds(range, self.size as u64)?;
+        if len == self.size as u64 {
+            return Some(*self);
+        }
+        if let Some(data) = self.inline_data() {
+            let mut handle = Self::inline(&data[start as usize..(start + len) as usize])?;
+            handle.timestamp = self.timestamp;
+            return Some(handle);
+        }
+        Some(Self {
+            offset: self.offset + start,
+            size: len as u32,
+            tag: SLICE_TAG,
+            ..*self
+        })
+    }
+
+    /// True if the payload travels inside this handle.
+    pub fn is_inline(&self) -> bool {
+        self.page_id == INLINE_PAGE_ID
+    }
+
+    /// The inline payload, or `None` for handles that point into shared memory
+    /// (or claim more than [`INLINE_CAPACITY`] bytes).
+    ///
+    /// Time: O(1) — borrows the handle's own bytes.
+    pub fn inline_data(&self) -> Option<&[u8]> {
+        if !self.is_inline() || self.size as usize > INLINE_CAPACITY {
+            return None;
+        }
+        let start = INLINE_DATA_START;
+        Some(&self.as_bytes()[start..start + self.size as usize])
     }
 
     /// Check if this handle has expired based on the given TTL (in milliseconds).
     ///
+    /// Only looks at the creation timestamp. An entry appended with its own
+    /// TTL, or renewed with `touch`, keeps its expiry in the arena; `resolve`
+    /// checks that one instead.
+    ///
     /// Time: O(1).
     pub fn is_expired(&self, ttl_ms: u64) -> bool {
         now_ms().saturating_sub(self.timestamp) > ttl_ms
@@ -68,10 +188,19 @@ impl OverflowHandle {
 
     /// Deserialize an `OverflowHandle` from a byte slice.
     ///
-    /// Returns `None` if the slice is not exactly `size_of::<OverflowHandle>()` bytes.
+    /// Accepts the current 48-byte encoding, the 24-byte layout-v1 encoding
+    /// and the 50-byte portable one from [`to_bytes`](Self::to_bytes) (told
+    /// apart by length).
+    /// Returns `None` for any other length, or an invalid portable encoding.
     ///
-    /// Time: O(1) — 24-byte memcpy.
+    /// Time: O(1) — 48-byte memcpy.
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() == OVERFLOW_HANDLE_V1_SIZE {
+            return Some(Self::from_v1_bytes(bytes));
+        }
+        if bytes.len() == OVERFLOW_HANDLE_WIRE_SIZE {
+            return Self::try_from_bytes(bytes).ok();
+        }
         if bytes.len() != std::mem::size_of::<Self>() {
             return None;
         }
@@ -86,6 +215,44 @@ impl OverflowHandle {
             Some(handle.assume_init())
         }
     }
+
+    /// Encode this handle in the 24-byte layout-v1 form understood by
+    /// libraries that predate layout v2.
+    ///
+    /// Returns `None` for inline handles, and if `offset` or `generation`
+    /// does not fit in 32 bits, which cannot happen for handles issued by a
+    /// v1 arena.
+    ///
+    /// Time: O(1).
+    pub fn to_v1_bytes(&self) -> Option<[u8; OVERFLOW_HANDLE_V1_SIZE]> {
+        if self.is_inline() {
+            return None;
+        }
+        let offset = u32::try_from(self.offset).ok()?;
+        let generation = u32::try_from(self.generation).ok()?;
+
+        let mut out = [0u8; OVERFLOW_HANDLE_V1_SIZE];
+        out[0..4].copy_from_slice(&self.page_id.to_ne_bytes());
+        out[4..8].copy_from_slice(&offset.to_ne_bytes());
+        out[8..12].copy_from_slice(&self.size.to_ne_bytes());
+        out[12..16].copy_from_slice(&generation.to_ne_bytes());
+        out[16..24].copy_from_slice(&self.timestamp.to_ne_bytes());
+        Some(out)
+    }
+
+    /// Decode the 24-byte layout-v1 form. Caller checks the length.
+    fn from_v1_bytes(bytes: &[u8]) -> Self {
+        let u32_at = |i: usize| u32::from_ne_bytes(bytes[i..i + 4].try_into().unwrap());
+        Self {
+            page_id: u32_at(0),
+            offset: u32_at(4) as u64,
+            size: u32_at(8),
+            generation: u32_at(12) as u64,
+            timestamp: u64::from_ne_bytes(bytes[16..24].try_into().unwrap()),
+            tag: 0,
+            instance_id: 0,
+        }
+    }
 }
 
 /// Selects which storage backend `PinnedBlobStore` uses.
@@ -109,7 +276,7 @@ mod tests {
 
     #[test]
     fn test_overflow_handle_size() {
-        assert_eq!(std::mem::size_of::<OverflowHandle>(), 24);
+        assert_eq!(std::mem::size_of::<OverflowHandle>(), 48);
     }
 
     #[test]
@@ -120,6 +287,43 @@ mod tests {
         assert_eq!(handle, restored);
     }
 
+    #[test]
+    fn test_overflow_handle_v1_roundtrip() {
+        let handle = OverflowHandle::new(42, 1024, 512, 7);
+        let v1 = handle.to_v1_bytes().unwrap();
+        assert_eq!(OverflowHandle::from_bytes(&v1).unwrap(), handle);
+
+        // Offsets beyond 4 GB have no v1 encoding
+        let wide = OverflowHandle::new(0, 5 << 30, 16, 1);
+        assert!(wide.to_v1_bytes().is_none());
+
+        // Neither encoding length is accepted as the other
+        assert!(OverflowHandle::from_bytes(&[0u8; 28]).is_none());
+    }
+
+    #[test]
+    fn test_overflow_handle_rejects_unreleased_sizes() {
+        let handle = OverflowHandle::new(3, 4096, 64, 9);
+        for len in [32, 40] {
+            assert!(OverflowHandle::from_bytes(&handle.as_bytes()[..len]).is_none());
+        }
+    }
+
+    #[test]
+    fn test_overflow_handle_inline() {
+        let handle = OverflowHandle::inline(b"sixteen bytes!!!").unwrap();
+        assert!(handle.is_inline());
+        assert_eq!(handle.inline_data().unwrap(), b"sixteen bytes!!!");
+        assert!(handle.to_v1_bytes().is_none());
+
+        // Survives serialisation
+        let restored = OverflowHandle::from_bytes(handle.as_bytes()).unwrap();
+        assert_eq!(restored.inline_data().unwrap(), b"sixteen bytes!!!");
+
+        assert!(OverflowHandle::inline(&[0u8; INLINE_CAPACITY + 1]).is_none());
+        assert!(OverflowHandle::new(0, 0, 4, 1).inline_data().is_none());
+    }
+
     #[test]
     fn test_overflow_handle_ttl() {
         let handle = OverflowHandle::new(0, 0, 100, 1);
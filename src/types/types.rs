@@ -1,3 +1,6 @@
+use super::OverflowHandle;
+use std::io::{IoSlice, SeekFrom};
+use std::ops::{Bound, RangeBounds};
 use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
@@ -13,6 +16,93 @@ pub fn now_ms() -> u64 {
         .as_millis() as u64
 }
 
+/// Copy `len` bytes of the concatenation of `bufs`, starting `skip` bytes
+/// in, to `dst`. Lets vectored appends write their slices straight into
+/// place, as if they were one buffer.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes of `len` bytes that overlap none of
+/// `bufs`, and `bufs` must hold at least `skip + len` bytes.
+///
+/// Time: O(len + bufs.len()).
+pub(crate) unsafe fn copy_gathered(
+    bufs: &[IoSlice<'_>],
+    mut skip: usize,
+    dst: *mut u8,
+    len: usize,
+) {
+    let mut written = 0;
+    for buf in bufs {
+        if written == len {
+            break;
+        }
+        if skip >= buf.len() {
+            skip -= buf.len();
+            continue;
+        }
+        let n = (buf.len() - skip).min(len - written);
+        unsafe { std::ptr::copy_nonoverlapping(buf.as_ptr().add(skip), dst.add(written), n) };
+        written += n;
+        skip = 0;
+    }
+}
+
+/// Total length of `bufs`.
+pub(crate) fn gathered_len(bufs: &[IoSlice<'_>]) -> usize {
+    bufs.iter().map(|buf| buf.len()).sum()
+}
+
+/// Start and length of `range` within `len` bytes, for the handles'
+/// `slice`. `None` if it is empty or does not fit.
+pub(crate) fn slice_bounds(range: impl RangeBounds<u64>, len: u64) -> Option<(u64, u64)> {
+    let start = match range.start_bound() {
+        Bound::Included(&at) => at,
+        Bound::Excluded(&at) => at.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&at) => at.checked_add(1)?,
+        Bound::Excluded(&at) => at,
+        Bound::Unbounded => len,
+    };
+    (start < end && end <= len).then_some((start, end - start))
+}
+
+/// Resolve `to` against a cursor at `pos` in a blob of `len` bytes, for
+/// the readers' `Seek` impls. Seeking past the end is allowed; before the
+/// start is `InvalidInput`.
+pub(crate) fn seek_position(len: u64, pos: u64, to: SeekFrom) -> std::io::Result<u64> {
+    let (base, delta) = match to {
+        SeekFrom::Start(at) => return Ok(at),
+        SeekFrom::End(delta) => (len, delta),
+        SeekFrom::Current(delta) => (pos, delta),
+    };
+    base.checked_add_signed(delta).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "seek before the start of the blob",
+        )
+    })
+}
+
+/// Largest alignment `append_aligned` and the `align` options accept: the
+/// OS page size, which heap pages and shared chunks are mapped at.
+pub const MAX_ALIGN: usize = 4096;
+
+/// Check that `align` is a power of two no larger than [`MAX_ALIGN`].
+pub(crate) fn check_align(align: usize) -> Result<()> {
+    if align.is_power_of_two() && align <= MAX_ALIGN {
+        Ok(())
+    } else {
+        Err(BlobError::InvalidAlignment(align))
+    }
+}
+
+/// TTL that never runs out. Pass it to `append_with_ttl` or `touch` to pin
+/// an entry until it is acknowledged.
+pub const TTL_NEVER: u64 = u64::MAX;
+
 /// Reference to data stored in the blob store.
 /// Supports both single-page and multi-page data.
 /// Total size: 32 bytes (still well under 1KB message queue limit)
@@ -82,9 +172,23 @@ impl BlobHandle {
         self.end_page_id != self.page_id
     }
 
-    /// Check if this handle has expired based on TTL
-    pub(crate) fn is_expired(&self, ttl_ms: u64) -> bool {
-        now_ms().saturating_sub(self.timestamp) > ttl_ms
+    /// Handle to the bytes `range` of this blob, counted from its start,
+    /// for passing a section of it on without copying. The slice's offset
+    /// and size point inside the entry, and it is read, renewed and
+    /// reference counted through the whole entry; see
+    /// [`PinnedBlobStore::retain`](crate::PinnedBlobStore::retain).
+    /// Deleting and purging take the entry's own handle.
+    ///
+    /// Returns `None` if the range is empty or out of bounds, or, in a
+    /// multi-page blob, starts 4 GB or more into its first page.
+    pub fn slice(&self, range: impl RangeBounds<u64>) -> Option<Self> {
+        let (start, len) = slice_bounds(range, self.total_size)?;
+        Some(Self {
+            offset: u32::try_from(self.offset as u64 + start).ok()?,
+            size: len.min(u32::MAX as u64) as u32,
+            total_size: len,
+            ..*self
+        })
     }
 
     /// Get the age of this handle in milliseconds
@@ -129,6 +233,56 @@ impl BlobHandle {
     }
 }
 
+/// Most dead letters a store (or shared backend) holds until they are
+/// taken. Further ones are still counted in `ProfileStats` but not kept.
+pub const DEAD_LETTER_CAPACITY: usize = 1024;
+
+/// An entry that expired before it was acknowledged, reported when the page
+/// or chunk holding it is freed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadLetter {
+    /// Handle the entry was (or could have been) read through. No longer
+    /// resolvable: the data is gone.
+    pub handle: DeadLetterHandle,
+    /// Age of the entry when it was dropped, in milliseconds. For a whole
+    /// shared chunk (`SharedChunk`), the age of the chunk since its first
+    /// write.
+    pub age_ms: u64,
+    /// Payload size in bytes. For a whole chunk, the bytes it had in use,
+    /// acknowledged entries included.
+    pub size: u64,
+}
+
+impl DeadLetter {
+    /// Entries this letter stands for: the unacknowledged ones of a
+    /// whole chunk, otherwise 1.
+    pub fn entries(&self) -> u64 {
+        match self.handle {
+            DeadLetterHandle::SharedChunk { unacked, .. } => unacked as u64,
+            _ => 1,
+        }
+    }
+}
+
+/// Handle of a dead-lettered entry, by backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadLetterHandle {
+    /// Entry of a heap page
+    Heap(BlobHandle),
+    /// Entry of a shared chunk
+    Shared(OverflowHandle),
+    /// Shared chunk of an arena without entry records, whose entries
+    /// cannot be told apart
+    SharedChunk {
+        /// Chunk id
+        page_id: u32,
+        /// Chunk generation
+        generation: u64,
+        /// Entries that were never acknowledged
+        unacked: u32,
+    },
+}
+
 /// Configuration for the blob store
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -143,6 +297,10 @@ pub struct Config {
 
     /// Default TTL for stored data (milliseconds, default: 30000)
     pub default_ttl_ms: u64,
+
+    /// Alignment of every payload appended without an explicit one; a
+    /// power of two up to [`MAX_ALIGN`] (default: 1, i.e. packed)
+    pub align: usize,
 }
 
 impl Default for Config {
@@ -152,6 +310,7 @@ impl Default for Config {
             prefetch_threshold: 0.8, // 80%
             decay_timeout_ms: 5000,  // 5 seconds
             default_ttl_ms: 30000,   // 30 seconds
+            align: 1,
         }
     }
 }
@@ -164,6 +323,7 @@ impl Config {
             prefetch_threshold: 0.8,
             decay_timeout_ms: 7000,
             default_ttl_ms: 30000,
+            align: 1,
         }
     }
 
@@ -174,8 +334,170 @@ impl Config {
             prefetch_threshold: 0.90, // 90% - less aggressive prefetch
             decay_timeout_ms: 1000,   // 1 second - faster cleanup
             default_ttl_ms: 30000,
+            align: 1,
         }
     }
+
+    /// Check that the values are usable.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if check_align(self.align).is_err() {
+            return Err(BlobError::InvalidConfig(
+                "align must be a power of two up to MAX_ALIGN",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// On-disk layout of a shared arena, fixed when the namespace is created.
+///
+/// Recorded in the control file; attachers always use the layout they find
+/// there, whatever they pass in their own `SharedConfig`.
+///
+/// The default layout has no entry records, so a chunk only counts its
+/// entries and acks. When such a chunk is freed with entries unacked, its
+/// dead letter covers the whole chunk (`DeadLetterHandle::SharedChunk`):
+/// how many were lost and the chunk's bytes in use, but not which entries.
+/// Per-entry dead letters need `entry_records`; they are aged from each
+/// entry's own write time, whole-chunk letters from the chunk's first write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaLayout {
+    /// Control-file and chunk-header format (default: 2).
+    ///
+    /// - `1`: 32-bit `used`/`generation`, chunks up to 4 GB. Attachable by
+    ///   every library version.
+    /// - `2`: 64-bit `chunk_size`, `used` and generation counters. Only
+    ///   attachable by libraries that understand layout v2.
+    pub version: u32,
+
+    /// Put producer-side counters (`used`, `entry_count`) and consumer-side
+    /// counters (`ack_count`, `empty_since`) on separate cache lines of the
+    /// chunk header, so appends and acks on different cores do not contend
+    /// for one line. Grows the header from 64 to 192 bytes. Requires v2.
+    pub split_counters: bool,
+
+    /// Sign every `OverflowHandle` with a SipHash-2-4 tag over
+    /// `(page_id, offset, size, generation)`, keyed by a random secret kept
+    /// in the control file. `resolve` and `acknowledge` drop handles whose
+    /// tag does not verify, so a peer cannot forge or alter a handle.
+    /// Requires v2.
+    pub authenticated_handles: bool,
+
+    /// Put a 48-byte record in front of every payload holding per-entry
+    /// state (own expiry, write time, ...). Needed by `append_with_ttl`,
+    /// per-entry dead letters and the other per-entry operations; costs 48 bytes
+    /// plus up to 7 bytes of padding per entry. Requires v2.
+    pub entry_records: bool,
+}
+
+impl Default for ArenaLayout {
+    fn default() -> Self {
+        Self {
+            version: 2,
+            split_counters: false,
+            authenticated_handles: false,
+            entry_records: false,
+        }
+    }
+}
+
+/// Policy shared by every process attached to a shared-memory namespace.
+///
+/// The creator writes these values into the control file; attachers adopt
+/// them instead of their own `Config`, so all processes agree on when data
+/// is dead. `SharedBackend::set_config` rewrites them at runtime and every
+/// attached process observes the change on its next operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedConfig {
+    /// TTL for stored data (milliseconds, default: 30000)
+    pub ttl_ms: u64,
+
+    /// How long to keep fully-acked chunks before freeing (milliseconds, default: 5000)
+    pub decay_timeout_ms: u64,
+
+    /// Maximum number of chunks ever allocated (`None` = unlimited)
+    pub max_chunks: Option<u32>,
+
+    /// Usage fraction (0.0 - 1.0) at which the next chunk is pre-allocated (default: 0.8)
+    pub prefetch_threshold: f32,
+
+    /// Upper bound on live chunk bytes in `/dev/shm` (`None` = unlimited)
+    pub byte_budget: Option<u64>,
+
+    /// Alignment of every payload appended without an explicit one; a
+    /// power of two up to [`MAX_ALIGN`] (default: 1, i.e. packed)
+    pub align: usize,
+
+    /// Arena layout — only honoured at creation, cannot be changed later.
+    /// The default has no entry records, so dead letters are reported per
+    /// chunk rather than per entry; see [`ArenaLayout`].
+    pub layout: ArenaLayout,
+}
+
+impl Default for SharedConfig {
+    fn default() -> Self {
+        Self::from(&Config::default())
+    }
+}
+
+impl From<&Config> for SharedConfig {
+    fn from(config: &Config) -> Self {
+        Self {
+            ttl_ms: config.default_ttl_ms,
+            decay_timeout_ms: config.decay_timeout_ms,
+            max_chunks: None,
+            prefetch_threshold: config.prefetch_threshold,
+            byte_budget: None,
+            align: config.align,
+            layout: ArenaLayout::default(),
+        }
+    }
+}
+
+impl SharedConfig {
+    /// Check that the values can be stored in the control file of an
+    /// arena with chunks of `chunk_size` bytes. Run on creation and on
+    /// every later `set_config`.
+    pub(crate) fn validate(&self, chunk_size: u64) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.prefetch_threshold) {
+            return Err(BlobError::InvalidConfig(
+                "prefetch_threshold must be within 0.0..=1.0",
+            ));
+        }
+        if self.max_chunks == Some(0) {
+            return Err(BlobError::InvalidConfig("max_chunks must be at least 1"));
+        }
+        if self.byte_budget == Some(0) {
+            return Err(BlobError::InvalidConfig("byte_budget must be non-zero"));
+        }
+        if self.byte_budget.is_some_and(|b| b < chunk_size) {
+            return Err(BlobError::InvalidConfig(
+                "byte_budget must hold at least one chunk",
+            ));
+        }
+        if check_align(self.align).is_err() {
+            return Err(BlobError::InvalidConfig(
+                "align must be a power of two up to MAX_ALIGN",
+            ));
+        }
+        if !(1..=2).contains(&self.layout.version) {
+            return Err(BlobError::InvalidConfig("unsupported layout version"));
+        }
+        if self.layout.split_counters && self.layout.version < 2 {
+            return Err(BlobError::InvalidConfig(
+                "split_counters requires layout v2",
+            ));
+        }
+        if self.layout.authenticated_handles && self.layout.version < 2 {
+            return Err(BlobError::InvalidConfig(
+                "authenticated_handles requires layout v2",
+            ));
+        }
+        if self.layout.entry_records && self.layout.version < 2 {
+            return Err(BlobError::InvalidConfig("entry_records requires layout v2"));
+        }
+        Ok(())
+    }
 }
 
 /// Errors that can occur in the blob store
@@ -195,6 +517,41 @@ pub enum BlobError {
 
     #[error("Page is full")]
     PageFull,
+
+    #[error("Invalid alignment {0} (must be a power of two up to {max})", max = MAX_ALIGN)]
+    InvalidAlignment(usize),
+
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(&'static str),
+
+    #[error("Unsupported shared layout (version {version}, flags {flags:#x})")]
+    UnsupportedLayout { version: u32, flags: u32 },
+
+    #[error(
+        "Handle belongs to another namespace instance (expected {expected:#x}, found {found:#x})"
+    )]
+    WrongInstance { expected: u64, found: u64 },
+
+    #[error("Handle does not fit the compact encoding")]
+    NotCompactable,
+
+    #[error("Invalid handle encoding: {0}")]
+    InvalidEncoding(&'static str),
+
+    #[error("Operation requires arena layout option `{0}`")]
+    LayoutFeatureRequired(&'static str),
+
+    #[error("Unknown consumer group {0}")]
+    UnknownGroup(u32),
+
+    #[error("Too many consumer groups (max: {max})")]
+    TooManyGroups { max: usize },
+
+    #[error("Entry is claimed by consumer {owner}")]
+    AlreadyClaimed { owner: u16 },
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 pub type Result<T> = std::result::Result<T, BlobError>;
@@ -0,0 +1,177 @@
+use super::overflow_handle::OverflowHandle;
+use super::types::BlobHandle;
+use std::marker::PhantomData;
+
+/// Plain-old-data: a type that can be stored as its raw bytes and read
+/// back from any bytes of its size, in this process or another one.
+///
+/// Implemented for the fixed-width integer and float primitives up to 64
+/// bits and for arrays of `Pod` types. `bool`, `char` and references are
+/// not `Pod`: not every bit pattern is a valid value of them. Neither are
+/// `usize` and `isize`, whose width differs between 32- and 64-bit peers,
+/// nor `u128` and `i128`, whose alignment has changed between compiler
+/// versions.
+///
+/// # Safety
+///
+/// Implement it only for types that are:
+///
+/// - `Copy`, and `#[repr(C)]` or `#[repr(transparent)]` over fields that
+///   are `Pod` themselves, so the layout is the same in every process;
+/// - free of padding bytes, which would be stored uninitialised;
+/// - valid for every bit pattern of their size (no `bool`, `char`, enums
+///   or `NonZero*` fields);
+/// - free of pointers and references of any kind, which mean nothing in
+///   another process or once the entry has been freed.
+///
+/// ```
+/// use stable_fragmented_buffer::Pod;
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(C)]
+/// struct Tick {
+///     price: f64,
+///     volume: u32,
+///     venue: u32,
+/// }
+///
+/// // SAFETY: repr(C), no padding, any bits are a valid Tick, no pointers
+/// unsafe impl Pod for Tick {}
+/// ```
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// View a `Pod` value as its bytes.
+pub(crate) fn pod_bytes<T: Pod>(value: &T) -> &[u8] {
+    // SAFETY: `Pod` types have no padding, so every byte is initialised
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+/// View a `Pod` value as mutable bytes, to read it in place.
+pub(crate) fn pod_bytes_mut<T: Pod>(value: &mut T) -> &mut [u8] {
+    // SAFETY: as above, and any bytes written form a valid `T`
+    unsafe { std::slice::from_raw_parts_mut(value as *mut T as *mut u8, size_of::<T>()) }
+}
+
+/// A `T` whose bytes are all zero; valid since any bits are.
+pub(crate) fn pod_zeroed<T: Pod>() -> T {
+    // SAFETY: `Pod` types are valid for every bit pattern
+    unsafe { std::mem::zeroed() }
+}
+
+/// Handle to an entry holding one `T`, from `append_value`. `H` is the
+/// untyped handle: [`BlobHandle`] for heap stores, [`OverflowHandle`] for
+/// shared arenas.
+///
+/// Everything but `view` takes the untyped handle, see [`raw`](Self::raw).
+pub struct Handle<T, H = BlobHandle> {
+    raw: H,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T, H> Handle<T, H> {
+    /// Type a handle to an entry known to hold a `T`.
+    pub(crate) fn new(raw: H) -> Self {
+        Self {
+            raw,
+            _value: PhantomData,
+        }
+    }
+
+    /// The untyped handle, for acknowledging, retaining, sending on, ...
+    pub fn raw(&self) -> &H {
+        &self.raw
+    }
+
+    /// Drop the type and keep the untyped handle.
+    pub fn into_raw(self) -> H {
+        self.raw
+    }
+}
+
+impl<T: Pod> Handle<T, BlobHandle> {
+    /// Type an untyped handle, e.g. one that was sent on. `None` unless the
+    /// entry is exactly `size_of::<T>()` bytes; since any bytes form a
+    /// valid `T`, the size is all that needs to match.
+    pub fn from_raw(raw: BlobHandle) -> Option<Self> {
+        (raw.total_size() == size_of::<T>() as u64).then_some(Self {
+            raw,
+            _value: PhantomData,
+        })
+    }
+}
+
+impl<T: Pod> Handle<T, OverflowHandle> {
+    /// Type an untyped handle, e.g. one read from a ring slot. `None`
+    /// unless the entry is exactly `size_of::<T>()` bytes; since any bytes
+    /// form a valid `T`, the size is all that needs to match.
+    pub fn from_raw(raw: OverflowHandle) -> Option<Self> {
+        (raw.size as usize == size_of::<T>()).then_some(Self {
+            raw,
+            _value: PhantomData,
+        })
+    }
+}
+
+// Manual impls: the derives would require `T` to implement them too.
+
+impl<T, H: Clone> Clone for Handle<T, H> {
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw.clone(),
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<T, H: Copy> Copy for Handle<T, H> {}
+
+impl<T, H: PartialEq> PartialEq for Handle<T, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<T, H: Eq> Eq for Handle<T, H> {}
+
+impl<T, H: std::hash::Hash> std::hash::Hash for Handle<T, H> {
+    fn hash<S: std::hash::Hasher>(&self, state: &mut S) {
+        self.raw.hash(state);
+    }
+}
+
+impl<T, H: std::fmt::Debug> std::fmt::Debug for Handle<T, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Handle")
+            .field("type", &std::any::type_name::<T>())
+            .field("raw", &self.raw)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_raw_checks_size() {
+        let exact = BlobHandle::new(1, 0, 8, 0);
+        assert_eq!(Handle::<u64>::from_raw(exact).unwrap().raw(), &exact);
+        assert!(Handle::<u32>::from_raw(exact).is_none());
+        assert!(Handle::<[u32; 2]>::from_raw(exact).is_some());
+
+        let shared = OverflowHandle::new(1, 64, 4, 0);
+        assert!(Handle::<f32, OverflowHandle>::from_raw(shared).is_some());
+        assert!(Handle::<f64, OverflowHandle>::from_raw(shared).is_none());
+        assert!(Handle::<u8, OverflowHandle>::from_raw(shared).is_none());
+    }
+}
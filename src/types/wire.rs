@@ -0,0 +1,358 @@
+//! Portable encoding of handles, for logs, JSON messages and consumers in
+//! other languages or on other machines.
+//!
+//! Unlike [`OverflowHandle::as_bytes`], which dumps the struct in native
+//! byte order, the encoding is fixed:
+//!
+//! ```text
+//! version u8 (= 1) | kind u8 | fields, little-endian
+//!
+//! kind 1, BlobHandle (34 bytes):
+//!   page_id u32 | offset u32 | generation u32 | end_page_id u32 |
+//!   total_size u64 | timestamp u64
+//!
+//! kind 2, OverflowHandle (50 bytes):
+//!   page_id u32 | size u32 | offset u64 | generation u64 | timestamp u64 |
+//!   tag u64 | instance_id u64
+//! ```
+//!
+//! An inline `OverflowHandle` carries its payload bytes in place of
+//! `offset` and `generation`, in order. The text form is the encoding in
+//! lowercase hex.
+
+use super::overflow_handle::{OverflowHandle, INLINE_CAPACITY, INLINE_PAGE_ID};
+use super::types::{BlobError, BlobHandle, Result};
+
+/// Version byte of the current encoding.
+pub const HANDLE_WIRE_VERSION: u8 = 1;
+
+/// Size of a [`BlobHandle`] in the portable encoding.
+pub const BLOB_HANDLE_WIRE_SIZE: usize = 34;
+
+/// Size of an [`OverflowHandle`] in the portable encoding.
+pub const OVERFLOW_HANDLE_WIRE_SIZE: usize = 50;
+
+const KIND_BLOB: u8 = 1;
+const KIND_OVERFLOW: u8 = 2;
+
+/// Little-endian writer over a fixed-size encoding.
+struct Encoder<const N: usize> {
+    out: [u8; N],
+    at: usize,
+}
+
+impl<const N: usize> Encoder<N> {
+    fn new(kind: u8) -> Self {
+        let mut out = [0u8; N];
+        out[0] = HANDLE_WIRE_VERSION;
+        out[1] = kind;
+        Self { out, at: 2 }
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> &mut Self {
+        self.out[self.at..self.at + bytes.len()].copy_from_slice(bytes);
+        self.at += bytes.len();
+        self
+    }
+}
+
+/// Little-endian reader over an encoding whose header has been checked.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Check the header of `bytes`, an encoding of `kind`, `size` bytes long.
+    fn new(bytes: &'a [u8], kind: u8, size: usize) -> Result<Self> {
+        if bytes.len() != size {
+            return Err(BlobError::InvalidEncoding("wrong length"));
+        }
+        if bytes[0] != HANDLE_WIRE_VERSION {
+            return Err(BlobError::InvalidEncoding("unknown version"));
+        }
+        if bytes[1] != kind {
+            return Err(BlobError::InvalidEncoding("wrong handle type"));
+        }
+        Ok(Self { bytes, at: 2 })
+    }
+
+    fn take<const W: usize>(&mut self) -> [u8; W] {
+        let field = self.bytes[self.at..self.at + W].try_into().unwrap();
+        self.at += W;
+        field
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take())
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.take())
+    }
+}
+
+impl BlobHandle {
+    /// Encode this handle in the portable form: little-endian, with a
+    /// version and type byte in front. See [`from_bytes`](Self::from_bytes).
+    ///
+    /// Time: O(1).
+    pub fn to_bytes(&self) -> [u8; BLOB_HANDLE_WIRE_SIZE] {
+        let mut e = Encoder::new(KIND_BLOB);
+        e.put(&self.page_id.to_le_bytes())
+            .put(&self.offset.to_le_bytes())
+            .put(&self.generation.to_le_bytes())
+            .put(&self.end_page_id.to_le_bytes())
+            .put(&self.total_size.to_le_bytes())
+            .put(&self.timestamp.to_le_bytes());
+        e.out
+    }
+
+    /// Decode a handle encoded with [`to_bytes`](Self::to_bytes), in this
+    /// process or any other.
+    ///
+    /// Errors: `InvalidEncoding` if the length, version or type byte is
+    /// wrong, or the fields describe no handle a store issues.
+    ///
+    /// Time: O(1).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut d = Decoder::new(bytes, KIND_BLOB, BLOB_HANDLE_WIRE_SIZE)?;
+        let (page_id, offset, generation, end_page_id) = (d.u32(), d.u32(), d.u32(), d.u32());
+        let (total_size, timestamp) = (d.u64(), d.u64());
+        if total_size == 0 || end_page_id < page_id {
+            return Err(BlobError::InvalidEncoding("inconsistent fields"));
+        }
+        Ok(Self {
+            page_id,
+            offset,
+            size: total_size.min(u32::MAX as u64) as u32,
+            timestamp,
+            generation,
+            end_page_id,
+            total_size,
+        })
+    }
+}
+
+impl OverflowHandle {
+    /// Encode this handle in the portable form: little-endian, with a
+    /// version and type byte in front. Unlike [`as_bytes`](Self::as_bytes)
+    /// it reads the same on machines of either byte order.
+    ///
+    /// Time: O(1).
+    pub fn to_bytes(&self) -> [u8; OVERFLOW_HANDLE_WIRE_SIZE] {
+        let mut e = Encoder::new(KIND_OVERFLOW);
+        e.put(&self.page_id.to_le_bytes())
+            .put(&self.size.to_le_bytes());
+        match self.inline_bytes() {
+            Some(data) => e.put(&data),
+            None => e
+                .put(&self.offset.to_le_bytes())
+                .put(&self.generation.to_le_bytes()),
+        };
+        e.put(&self.timestamp.to_le_bytes())
+            .put(&self.tag.to_le_bytes())
+            .put(&self.instance_id.to_le_bytes());
+        e.out
+    }
+
+    /// Decode a handle encoded with [`to_bytes`](Self::to_bytes), in this
+    /// process or any other. [`from_bytes`](Self::from_bytes) accepts the
+    /// portable form too, but without saying what is wrong.
+    ///
+    /// Errors: `InvalidEncoding` if the length, version or type byte is
+    /// wrong, or an inline handle claims more than [`INLINE_CAPACITY`]
+    /// bytes.
+    ///
+    /// Time: O(1).
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut d = Decoder::new(bytes, KIND_OVERFLOW, OVERFLOW_HANDLE_WIRE_SIZE)?;
+        let (page_id, size) = (d.u32(), d.u32());
+        let (offset, generation) = if page_id == INLINE_PAGE_ID {
+            if size as usize > INLINE_CAPACITY {
+                return Err(BlobError::InvalidEncoding("inconsistent fields"));
+            }
+            (u64::from_ne_bytes(d.take()), u64::from_ne_bytes(d.take()))
+        } else {
+            (d.u64(), d.u64())
+        };
+        Ok(Self {
+            page_id,
+            size,
+            offset,
+            generation,
+            timestamp: d.u64(),
+            tag: d.u64(),
+            instance_id: d.u64(),
+        })
+    }
+
+    /// The `offset` and `generation` fields as the payload bytes they hold
+    /// in an inline handle; `None` for other handles.
+    fn inline_bytes(&self) -> Option<[u8; INLINE_CAPACITY]> {
+        self.is_inline().then(|| {
+            let mut data = [0u8; INLINE_CAPACITY];
+            data[..8].copy_from_slice(&self.offset.to_ne_bytes());
+            data[8..].copy_from_slice(&self.generation.to_ne_bytes());
+            data
+        })
+    }
+}
+
+/// Write `bytes` as lowercase hex.
+fn write_hex(f: &mut std::fmt::Formatter<'_>, bytes: &[u8]) -> std::fmt::Result {
+    bytes.iter().try_for_each(|b| write!(f, "{b:02x}"))
+}
+
+/// Parse hex of either case into exactly `N` bytes.
+fn parse_hex<const N: usize>(s: &str) -> Result<[u8; N]> {
+    let digits = s.as_bytes();
+    if digits.len() != 2 * N {
+        return Err(BlobError::InvalidEncoding("wrong length"));
+    }
+    let nibble = |c: u8| {
+        (c as char)
+            .to_digit(16)
+            .ok_or(BlobError::InvalidEncoding("not hex"))
+    };
+    let mut out = [0u8; N];
+    for (byte, pair) in out.iter_mut().zip(digits.chunks_exact(2)) {
+        *byte = (nibble(pair[0])? << 4 | nibble(pair[1])?) as u8;
+    }
+    Ok(out)
+}
+
+/// The portable encoding in lowercase hex, for logs and text messages.
+/// Parses back with `str::parse`.
+impl std::fmt::Display for BlobHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_hex(f, &self.to_bytes())
+    }
+}
+
+impl std::str::FromStr for BlobHandle {
+    type Err = BlobError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_bytes(&parse_hex::<BLOB_HANDLE_WIRE_SIZE>(s)?)
+    }
+}
+
+/// The portable encoding in lowercase hex, for logs and text messages.
+/// Parses back with `str::parse`.
+impl std::fmt::Display for OverflowHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_hex(f, &self.to_bytes())
+    }
+}
+
+impl std::str::FromStr for OverflowHandle {
+    type Err = BlobError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::try_from_bytes(&parse_hex::<OVERFLOW_HANDLE_WIRE_SIZE>(s)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_handle_wire_roundtrip() {
+        let single = BlobHandle::new(7, 128, 40, 3);
+        let multi = BlobHandle::new_multi_page(9, 64, 12, 5 << 30, 2);
+        let slice = multi.slice(10..20).unwrap();
+        for handle in [single, multi, slice] {
+            let bytes = handle.to_bytes();
+            assert_eq!(BlobHandle::from_bytes(&bytes).unwrap(), handle);
+            let text = handle.to_string();
+            assert_eq!(text.len(), 2 * BLOB_HANDLE_WIRE_SIZE);
+            assert_eq!(text.parse::<BlobHandle>().unwrap(), handle);
+            assert_eq!(text.to_uppercase().parse::<BlobHandle>().unwrap(), handle);
+        }
+
+        // Fixed byte order
+        let bytes = single.to_bytes();
+        assert_eq!(bytes[..6], [HANDLE_WIRE_VERSION, KIND_BLOB, 7, 0, 0, 0]);
+        assert_eq!(bytes[6..10], 128u32.to_le_bytes());
+    }
+
+    #[test]
+    fn test_overflow_handle_wire_roundtrip() {
+        let mut tagged = OverflowHandle::new(3, 5 << 30, 64, 9);
+        tagged.tag = 0xDEAD_BEEF;
+        tagged.instance_id = 0xFEED;
+        let inline = OverflowHandle::inline(b"sixteen bytes!!!").unwrap();
+        for handle in [tagged, inline] {
+            let bytes = handle.to_bytes();
+            assert_eq!(OverflowHandle::try_from_bytes(&bytes).unwrap(), handle);
+            assert_eq!(OverflowHandle::from_bytes(&bytes).unwrap(), handle);
+            let text = handle.to_string();
+            assert_eq!(text.parse::<OverflowHandle>().unwrap(), handle);
+        }
+
+        // Inline payloads keep their byte order
+        assert_eq!(&inline.to_bytes()[10..26], b"sixteen bytes!!!");
+        assert_eq!(tagged.to_bytes()[10..18], (5u64 << 30).to_le_bytes());
+    }
+
+    #[test]
+    fn test_handle_wire_validation() {
+        let blob = BlobHandle::new(1, 0, 8, 1);
+        let overflow = OverflowHandle::new(1, 0, 8, 1);
+        let invalid = |r: Result<_>| match r {
+            Err(BlobError::InvalidEncoding(why)) => why,
+            other => panic!("expected InvalidEncoding, got {other:?}"),
+        };
+
+        assert_eq!(
+            invalid(BlobHandle::from_bytes(&blob.to_bytes()[..33]).map(|_| ())),
+            "wrong length"
+        );
+        let mut bytes = blob.to_bytes();
+        bytes[0] = 2;
+        assert_eq!(
+            invalid(BlobHandle::from_bytes(&bytes).map(|_| ())),
+            "unknown version"
+        );
+        bytes = blob.to_bytes();
+        bytes[18..26].copy_from_slice(&0u64.to_le_bytes());
+        assert_eq!(
+            invalid(BlobHandle::from_bytes(&bytes).map(|_| ())),
+            "inconsistent fields"
+        );
+
+        // Neither handle type decodes as the other
+        assert_eq!(
+            invalid(
+                overflow.to_string()[..2 * BLOB_HANDLE_WIRE_SIZE]
+                    .parse::<BlobHandle>()
+                    .map(|_| ())
+            ),
+            "wrong handle type"
+        );
+        assert_eq!(
+            invalid(OverflowHandle::try_from_bytes(&blob.to_bytes()).map(|_| ())),
+            "wrong length"
+        );
+        let mut bytes = overflow.to_bytes();
+        bytes[1] = KIND_BLOB;
+        assert!(OverflowHandle::from_bytes(&bytes).is_none());
+
+        let mut inline = OverflowHandle::inline(b"abc").unwrap().to_bytes();
+        inline[6] = INLINE_CAPACITY as u8 + 1;
+        assert_eq!(
+            invalid(OverflowHandle::try_from_bytes(&inline).map(|_| ())),
+            "inconsistent fields"
+        );
+        assert_eq!(
+            invalid(
+                "zz".repeat(OVERFLOW_HANDLE_WIRE_SIZE)
+                    .parse::<OverflowHandle>()
+                    .map(|_| ())
+            ),
+            "not hex"
+        );
+    }
+}